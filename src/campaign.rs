@@ -0,0 +1,281 @@
+use bevy::{prelude::*, utils::HashSet};
+
+use crate::{
+    level_loader::{Campaign, LoadedCampaign},
+    menu::{spawn_button, ButtonActivated},
+    GameState,
+};
+
+pub fn campaign_plugin(app: &mut App) {
+    app.init_resource::<CurrentLevel>()
+        .init_resource::<CampaignProgress>()
+        .init_resource::<Lives>()
+        .init_resource::<Score>()
+        .init_resource::<LevelItems>()
+        .add_systems(OnEnter(GameState::Menu), new_game)
+        .add_systems(OnEnter(GameState::LevelComplete), display_level_complete)
+        .add_systems(
+            Update,
+            start_next_level.run_if(in_state(GameState::LevelComplete)),
+        )
+        .add_systems(
+            OnEnter(GameState::CampaignComplete),
+            display_campaign_complete,
+        )
+        .add_systems(
+            Update,
+            back_to_menu.run_if(in_state(GameState::CampaignComplete)),
+        )
+        .add_systems(OnEnter(GameState::GameOver), display_game_over)
+        .add_systems(
+            Update,
+            game_over_action.run_if(in_state(GameState::GameOver)),
+        );
+}
+
+/// Index of the level being played in the loaded [`Campaign`].
+#[derive(Resource, Default)]
+pub struct CurrentLevel(pub usize);
+
+/// Levels of the campaign that were finished at least once.
+#[derive(Resource, Default)]
+pub struct CampaignProgress {
+    completed: HashSet<usize>,
+}
+
+impl CampaignProgress {
+    pub fn complete(&mut self, level: usize) {
+        self.completed.insert(level);
+    }
+
+    pub fn is_completed(&self, level: usize) -> bool {
+        self.completed.contains(&level)
+    }
+
+    pub fn is_unlocked(&self, level: usize) -> bool {
+        level == 0 || self.is_completed(level - 1)
+    }
+}
+
+/// Lives a new game starts with.
+pub const STARTING_LIVES: u32 = 3;
+
+/// Lives left, the game is over when the last one is lost.
+#[derive(Resource)]
+pub struct Lives(pub u32);
+
+impl Default for Lives {
+    fn default() -> Self {
+        Self(STARTING_LIVES)
+    }
+}
+
+/// Points from the items picked up since the game started.
+#[derive(Resource, Default)]
+pub struct Score(pub u32);
+
+/// Items of one kind in the current level.
+#[derive(Default, Clone, Copy)]
+pub struct ItemCount {
+    pub collected: u32,
+    pub total: u32,
+}
+
+impl std::fmt::Display for ItemCount {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}/{}", self.collected, self.total)
+    }
+}
+
+/// Items of the current level, or of the last one finished.
+#[derive(Resource, Default)]
+pub struct LevelItems {
+    pub coins: ItemCount,
+    pub gems: ItemCount,
+    /// Score when the level started, given back when retrying it.
+    pub start_score: u32,
+}
+
+fn new_game(mut lives: ResMut<Lives>, mut score: ResMut<Score>) {
+    *lives = Lives::default();
+    *score = Score::default();
+}
+
+#[derive(Resource)]
+struct LevelCompleteTimer(Timer);
+
+fn display_level_complete(
+    mut commands: Commands,
+    current_level: Res<CurrentLevel>,
+    items: Res<LevelItems>,
+    score: Res<Score>,
+    campaign: Res<LoadedCampaign>,
+    campaigns: Res<Assets<Campaign>>,
+) {
+    let campaign = campaigns.get(&campaign.campaign).unwrap();
+
+    commands
+        .spawn((
+            Node {
+                width: Val::Percent(100.0),
+                height: Val::Percent(100.0),
+                align_items: AlignItems::Center,
+                justify_content: JustifyContent::Center,
+                flex_direction: FlexDirection::Column,
+                ..default()
+            },
+            StateScoped(GameState::LevelComplete),
+        ))
+        .with_children(|p| {
+            p.spawn((
+                Text::new("Level Complete!"),
+                TextFont {
+                    font_size: 80.0,
+                    ..default()
+                },
+            ));
+            p.spawn((
+                Text::new(format!(
+                    "{} coins, {} gems - Score: {}",
+                    items.coins, items.gems, score.0
+                )),
+                TextFont {
+                    font_size: 30.0,
+                    ..default()
+                },
+            ));
+            p.spawn((
+                Text::new(format!("Next: {}", campaign.levels[current_level.0].name)),
+                TextFont {
+                    font_size: 40.0,
+                    ..default()
+                },
+            ));
+        });
+
+    commands.insert_resource(LevelCompleteTimer(Timer::from_seconds(
+        1.5,
+        TimerMode::Once,
+    )));
+}
+
+fn start_next_level(
+    mut next: ResMut<NextState<GameState>>,
+    mut timer: ResMut<LevelCompleteTimer>,
+    time: Res<Time>,
+) {
+    if timer.0.tick(time.delta()).just_finished() {
+        next.set(GameState::Game);
+    }
+}
+
+fn display_campaign_complete(mut commands: Commands, score: Res<Score>) {
+    commands
+        .spawn((
+            Node {
+                width: Val::Percent(100.0),
+                height: Val::Percent(100.0),
+                align_items: AlignItems::Center,
+                justify_content: JustifyContent::Center,
+                flex_direction: FlexDirection::Column,
+                ..default()
+            },
+            StateScoped(GameState::CampaignComplete),
+        ))
+        .with_children(|p| {
+            p.spawn((
+                Text::new("Campaign Complete!"),
+                TextFont {
+                    font_size: 80.0,
+                    ..default()
+                },
+            ));
+            p.spawn((
+                Text::new(format!("Score: {}", score.0)),
+                TextFont {
+                    font_size: 40.0,
+                    ..default()
+                },
+            ));
+            p.spawn((
+                Text::new("Press any key to return to the menu"),
+                TextFont {
+                    font_size: 30.0,
+                    ..default()
+                },
+            ));
+        });
+}
+
+fn back_to_menu(
+    mut next: ResMut<NextState<GameState>>,
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    mouse_input: Res<ButtonInput<MouseButton>>,
+    gamepads: Query<&Gamepad>,
+) {
+    if keyboard_input.get_just_pressed().next().is_some()
+        || mouse_input.get_just_pressed().next().is_some()
+        || gamepads
+            .iter()
+            .any(|gamepad| gamepad.get_just_pressed().next().is_some())
+    {
+        next.set(GameState::Menu);
+    }
+}
+
+#[derive(Component)]
+enum GameOverButton {
+    Retry,
+    Menu,
+}
+
+fn display_game_over(mut commands: Commands) {
+    commands
+        .spawn((
+            Node {
+                width: Val::Percent(100.0),
+                height: Val::Percent(100.0),
+                align_items: AlignItems::Center,
+                justify_content: JustifyContent::Center,
+                flex_direction: FlexDirection::Column,
+                ..default()
+            },
+            StateScoped(GameState::GameOver),
+        ))
+        .with_children(|p| {
+            p.spawn((
+                Text::new("Game Over"),
+                TextFont {
+                    font_size: 80.0,
+                    ..default()
+                },
+            ));
+            spawn_button(p, "Retry", GameOverButton::Retry);
+            spawn_button(p, "Menu", GameOverButton::Menu);
+        });
+}
+
+/// Retry restarts the current level with all lives back, and without the points of the failed
+/// attempt.
+fn game_over_action(
+    mut activated: EventReader<ButtonActivated>,
+    buttons: Query<&GameOverButton>,
+    mut lives: ResMut<Lives>,
+    mut score: ResMut<Score>,
+    items: Res<LevelItems>,
+    mut next: ResMut<NextState<GameState>>,
+) {
+    for ButtonActivated(entity) in activated.read() {
+        let Ok(button) = buttons.get(*entity) else {
+            continue;
+        };
+        match button {
+            GameOverButton::Retry => {
+                *lives = Lives::default();
+                score.0 = items.start_score;
+                next.set(GameState::Game);
+            }
+            GameOverButton::Menu => next.set(GameState::Menu),
+        }
+    }
+}
@@ -0,0 +1,308 @@
+use bevy::{color::palettes::tailwind, core::FrameCount, prelude::*};
+
+use crate::{
+    input::{Action, Bindings, BindingsPath, MenuAction},
+    menu::{spawn_button, ButtonActivated},
+    GameState,
+};
+
+pub fn controls_plugin(app: &mut App) {
+    app.add_systems(Startup, load_bindings)
+        .add_systems(OnEnter(GameState::Controls), display_controls)
+        .add_systems(OnExit(GameState::Controls), stop_rebinding)
+        .add_systems(
+            Update,
+            (controls_action, capture_binding, update_binding_labels)
+                .chain()
+                .run_if(in_state(GameState::Controls)),
+        );
+}
+
+#[derive(Component)]
+enum ControlsButton {
+    Rebind(Action),
+    Back,
+}
+
+#[derive(Component)]
+struct BindingLabel(Action);
+
+/// Action waiting for a key press to be rebound.
+#[derive(Resource, Default)]
+pub struct Rebinding {
+    action: Option<Action>,
+    /// Frame the rebind started on, whose key press activated the button instead of being the
+    /// new binding.
+    started: u32,
+}
+
+/// Run condition for the menu navigation, as the arrow keys are bindings too.
+pub fn waiting_for_key(rebinding: Option<Res<Rebinding>>) -> bool {
+    rebinding.is_some_and(|rebinding| rebinding.action.is_some())
+}
+
+fn load_bindings(mut commands: Commands, path: Res<BindingsPath>) {
+    commands.insert_resource(Bindings::load(&path.0));
+}
+
+fn display_controls(mut commands: Commands) {
+    commands.insert_resource(Rebinding::default());
+
+    commands
+        .spawn((
+            Node {
+                width: Val::Percent(100.0),
+                height: Val::Percent(100.0),
+                align_items: AlignItems::Center,
+                justify_content: JustifyContent::Center,
+                flex_direction: FlexDirection::Column,
+                ..default()
+            },
+            StateScoped(GameState::Controls),
+        ))
+        .with_children(|p| {
+            p.spawn((
+                Text::new("Controls"),
+                TextFont {
+                    font_size: 80.0,
+                    ..default()
+                },
+            ));
+            for action in Action::ALL {
+                p.spawn(Node {
+                    align_items: AlignItems::Center,
+                    column_gap: Val::Px(20.0),
+                    ..default()
+                })
+                .with_children(|p| {
+                    p.spawn((
+                        Text::new(action.label()),
+                        TextFont {
+                            font_size: 30.0,
+                            ..default()
+                        },
+                        Node {
+                            width: Val::Px(200.0),
+                            ..default()
+                        },
+                    ));
+                    p.spawn((
+                        Button,
+                        Node {
+                            width: Val::Px(350.0),
+                            height: Val::Px(50.0),
+                            border: UiRect::all(Val::Px(5.0)),
+                            justify_content: JustifyContent::Center,
+                            align_items: AlignItems::Center,
+                            margin: UiRect::all(Val::Px(5.0)),
+                            ..default()
+                        },
+                        BorderColor(Color::BLACK),
+                        BorderRadius::all(Val::Px(10.0)),
+                        BackgroundColor(tailwind::BLUE_700.into()),
+                        ControlsButton::Rebind(action),
+                    ))
+                    .with_child((
+                        Text::default(),
+                        TextFont {
+                            font_size: 22.0,
+                            ..default()
+                        },
+                        TextColor(Color::srgb(0.9, 0.9, 0.9)),
+                        BindingLabel(action),
+                    ));
+                });
+            }
+            spawn_button(p, "Back", ControlsButton::Back);
+        });
+}
+
+fn stop_rebinding(mut commands: Commands) {
+    commands.remove_resource::<Rebinding>();
+}
+
+fn controls_action(
+    mut activated: EventReader<ButtonActivated>,
+    buttons: Query<&ControlsButton>,
+    mut next: ResMut<NextState<GameState>>,
+    mut rebinding: ResMut<Rebinding>,
+    frame: Res<FrameCount>,
+) {
+    for ButtonActivated(entity) in activated.read() {
+        let Ok(button) = buttons.get(*entity) else {
+            continue;
+        };
+        match button {
+            // Confirming with a key while rebinding binds that key instead
+            ControlsButton::Rebind(_) if rebinding.action.is_some() => {}
+            ControlsButton::Rebind(action) => {
+                rebinding.action = Some(*action);
+                rebinding.started = frame.0;
+            }
+            ControlsButton::Back => next.set(GameState::Menu),
+        }
+    }
+}
+
+fn capture_binding(
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    menu_actions: Res<ButtonInput<MenuAction>>,
+    mut rebinding: ResMut<Rebinding>,
+    mut bindings: ResMut<Bindings>,
+    path: Res<BindingsPath>,
+    frame: Res<FrameCount>,
+) {
+    let Some(action) = rebinding.action else {
+        return;
+    };
+    if rebinding.started == frame.0 {
+        return;
+    }
+    let Some(key) = keyboard_input.get_just_pressed().next() else {
+        if menu_actions.just_pressed(MenuAction::Back) {
+            rebinding.action = None;
+        }
+        return;
+    };
+    rebinding.action = None;
+    if *key == KeyCode::Escape && action != Action::Pause {
+        // cancel
+        return;
+    }
+    bindings.rebind(action, *key);
+    bindings.save(&path.0);
+}
+
+fn update_binding_labels(
+    mut labels: Query<(&mut Text, &BindingLabel)>,
+    bindings: Res<Bindings>,
+    rebinding: Res<Rebinding>,
+) {
+    if !bindings.is_changed() && !rebinding.is_changed() {
+        return;
+    }
+    for (mut text, label) in &mut labels {
+        text.0 = if rebinding.action == Some(label.0) {
+            "Press a key...".to_string()
+        } else {
+            bindings
+                .keys(label.0)
+                .iter()
+                .map(|key| format!("{key:?}"))
+                .collect::<Vec<_>>()
+                .join(", ")
+        };
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::{
+        input::{
+            keyboard::{Key, KeyboardInput, NativeKey},
+            ButtonState, InputPlugin,
+        },
+        state::app::StatesPlugin,
+    };
+
+    use super::*;
+    use crate::{
+        input::input_plugin,
+        menu::{menu_plugin, FocusedButton},
+    };
+
+    fn press(app: &mut App, key_code: KeyCode) {
+        for state in [ButtonState::Pressed, ButtonState::Released] {
+            app.world_mut().send_event(KeyboardInput {
+                key_code,
+                logical_key: Key::Unidentified(NativeKey::Unidentified),
+                state,
+                repeat: false,
+                window: Entity::PLACEHOLDER,
+            });
+            app.update();
+        }
+    }
+
+    /// Bindings file of a test, in the temporary directory and initially missing.
+    fn bindings_path(test: &str) -> BindingsPath {
+        let path = std::env::temp_dir().join(format!("bevy_workshop_{test}.cfg"));
+        let _ = std::fs::remove_file(&path);
+        BindingsPath(path)
+    }
+
+    /// Controls screen with the Rebind buttons stacked in `Action::ALL` order and the one of
+    /// `action` focused.
+    fn controls_app(path: BindingsPath, action: Action) -> App {
+        let mut app = App::new();
+        app.add_plugins((
+            MinimalPlugins,
+            InputPlugin,
+            StatesPlugin,
+            input_plugin,
+            menu_plugin,
+            controls_plugin,
+        ))
+        .insert_resource(path)
+        .insert_state(GameState::Controls);
+        app.update();
+
+        let mut buttons = app
+            .world_mut()
+            .query::<(Entity, &ControlsButton, &mut GlobalTransform)>();
+        let mut focused = None;
+        for (entity, button, mut transform) in buttons.iter_mut(app.world_mut()) {
+            if let ControlsButton::Rebind(rebound) = button {
+                let row = Action::ALL.iter().position(|a| a == rebound).unwrap();
+                *transform = GlobalTransform::from_xyz(400.0, 100.0 * row as f32, 0.0);
+                if *rebound == action {
+                    focused = Some(entity);
+                }
+            }
+        }
+        app.world_mut().resource_mut::<FocusedButton>().0 = focused;
+        app
+    }
+
+    #[test]
+    fn confirm_key_starts_rebinding_without_being_bound() {
+        let path = bindings_path("confirm_key_starts_rebinding");
+        let mut app = controls_app(path.clone(), Action::Jump);
+
+        press(&mut app, KeyCode::Enter);
+        assert_eq!(
+            app.world().resource::<Rebinding>().action,
+            Some(Action::Jump)
+        );
+        press(&mut app, KeyCode::KeyK);
+        assert_eq!(app.world().resource::<Rebinding>().action, None);
+        let bindings = app.world().resource::<Bindings>();
+        assert_eq!(
+            bindings.keys(Action::Jump),
+            [KeyCode::KeyK, KeyCode::ArrowUp]
+        );
+        // K was a dash key, it gets the previous jump key instead
+        assert_eq!(
+            bindings.keys(Action::Dash),
+            [KeyCode::ShiftLeft, KeyCode::Space]
+        );
+        assert_eq!(Bindings::load(&path.0), *bindings);
+    }
+
+    #[test]
+    fn focus_stays_while_rebinding() {
+        let mut app = controls_app(bindings_path("focus_stays_while_rebinding"), Action::Jump);
+        let focused = app.world().resource::<FocusedButton>().0;
+
+        press(&mut app, KeyCode::Enter);
+        press(&mut app, KeyCode::ArrowDown);
+        assert_eq!(app.world().resource::<FocusedButton>().0, focused);
+        assert_eq!(
+            app.world().resource::<Bindings>().keys(Action::Jump),
+            [KeyCode::ArrowDown, KeyCode::ArrowUp]
+        );
+
+        press(&mut app, KeyCode::ArrowDown);
+        assert_ne!(app.world().resource::<FocusedButton>().0, focused);
+    }
+}
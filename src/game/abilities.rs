@@ -0,0 +1,207 @@
+use bevy::{
+    ecs::system::EntityCommands,
+    math::bounding::{Aabb2d, IntersectsVolume},
+    prelude::*,
+};
+
+use crate::{
+    campaign::CurrentLevel,
+    input::Action,
+    level_loader::{Ability, Campaign, Level, LoadedCampaign},
+    GameState,
+};
+
+use super::{
+    player::{player_aabb, PlayerPhysics},
+    AbilityPickup, AudioTrigger, Contacts, Dash, DoubleJump, JumpInput, Player, Velocity, WallJump,
+    TILE_SIZE,
+};
+
+fn grant(player: &mut EntityCommands, ability: Ability) {
+    match ability {
+        Ability::DoubleJump => player.insert(DoubleJump::default()),
+        Ability::WallJump => player.insert(WallJump),
+        Ability::Dash => player.insert(Dash::default()),
+    };
+}
+
+pub(super) fn grant_level_abilities(
+    mut commands: Commands,
+    player: Query<Entity, With<Player>>,
+    campaign: Res<LoadedCampaign>,
+    current_level: Res<CurrentLevel>,
+    campaigns: Res<Assets<Campaign>>,
+    levels: Res<Assets<Level>>,
+) {
+    let campaign = campaigns.get(&campaign.campaign).unwrap();
+    let level = levels.get(&campaign.levels[current_level.0].level).unwrap();
+    for player in &player {
+        let mut player = commands.entity(player);
+        for ability in &level.abilities {
+            grant(&mut player, *ability);
+        }
+    }
+}
+
+pub(super) fn collect_pickups(
+    mut commands: Commands,
+    player: Query<(Entity, &Transform), With<Player>>,
+    pickups: Query<(Entity, &Transform, &AbilityPickup)>,
+) {
+    let (player, player_transform) = player.single();
+    let player_aabb = player_aabb(player_transform);
+    for (pickup, transform, AbilityPickup(ability)) in &pickups {
+        let pickup_aabb = Aabb2d::new(transform.translation.xy(), Vec2::splat(TILE_SIZE / 4.0));
+        if player_aabb.intersects(&pickup_aabb) {
+            grant(&mut commands.entity(player), *ability);
+            commands.entity(pickup).despawn();
+        }
+    }
+}
+
+/// Jump off a wall the player is touching in the air, away from it.
+#[allow(clippy::type_complexity)]
+pub(super) fn wall_jump(
+    mut player: Query<(&mut Velocity, &mut JumpInput, &Contacts), (With<Player>, With<WallJump>)>,
+    time: Res<Time>,
+    physics: Res<PlayerPhysics>,
+    mut audio_triggers: EventWriter<AudioTrigger>,
+) {
+    let Ok((mut velocity, mut jump, contacts)) = player.get_single_mut() else {
+        return;
+    };
+    if contacts.on_ground() || velocity.dashing {
+        return;
+    }
+    let away = if contacts.wall_left().is_some() {
+        1.0
+    } else if contacts.wall_right().is_some() {
+        -1.0
+    } else {
+        return;
+    };
+    if !jump.take_buffered(time.elapsed_secs(), physics.jump_buffer) {
+        return;
+    }
+    audio_triggers.send(AudioTrigger::Jump);
+    velocity.jumping = true;
+    velocity.current = Vec2::new(away * physics.wall_jump_push, physics.jump_impulse);
+}
+
+pub(super) fn double_jump(
+    mut player: Query<(&mut Velocity, &mut JumpInput, &mut DoubleJump, &Contacts), With<Player>>,
+    time: Res<Time>,
+    physics: Res<PlayerPhysics>,
+    mut audio_triggers: EventWriter<AudioTrigger>,
+) {
+    let Ok((mut velocity, mut jump, mut double_jump, contacts)) = player.get_single_mut() else {
+        return;
+    };
+    if contacts.on_ground() {
+        double_jump.used = false;
+        return;
+    }
+    if double_jump.used
+        || velocity.dashing
+        || !jump.take_buffered(time.elapsed_secs(), physics.jump_buffer)
+    {
+        return;
+    }
+    audio_triggers.send(AudioTrigger::DoubleJump);
+    double_jump.used = true;
+    velocity.jumping = true;
+    velocity.current.y = physics.double_jump_impulse;
+}
+
+/// Slow down the fall while pressed against a wall.
+#[allow(clippy::type_complexity)]
+pub(super) fn wall_slide(
+    mut player: Query<(&mut Velocity, &Contacts), (With<Player>, With<WallJump>)>,
+    physics: Res<PlayerPhysics>,
+) {
+    let Ok((mut velocity, contacts)) = player.get_single_mut() else {
+        return;
+    };
+    let on_wall = contacts.wall_left().is_some() || contacts.wall_right().is_some();
+    if on_wall && !contacts.on_ground() {
+        velocity.current.y = velocity.current.y.max(-physics.wall_slide_speed);
+    }
+}
+
+/// Start a dash in the facing direction, and keep its speed until it ends.
+pub(super) fn dash(
+    actions: Res<ButtonInput<Action>>,
+    mut player: Query<(&mut Velocity, &mut Dash, &Sprite), With<Player>>,
+    time: Res<Time>,
+    physics: Res<PlayerPhysics>,
+    mut audio_triggers: EventWriter<AudioTrigger>,
+) {
+    let Ok((mut velocity, mut dash, sprite)) = player.get_single_mut() else {
+        return;
+    };
+    let now = time.elapsed_secs();
+
+    let held = actions.pressed(Action::Dash);
+    if held && !dash.held && now >= dash.ready_at {
+        audio_triggers.send(AudioTrigger::Dash);
+        dash.until = now + physics.dash_duration;
+        dash.ready_at = now + physics.dash_cooldown;
+        dash.direction = if sprite.flip_x { -1.0 } else { 1.0 };
+        // The dash cancels the vertical speed of a jump started on the same tick
+        velocity.jumping = false;
+    }
+    dash.held = held;
+
+    let was_dashing = velocity.dashing;
+    velocity.dashing = now < dash.until;
+    if velocity.dashing {
+        velocity.current = Vec2::new(dash.direction * physics.dash_speed, 0.0);
+    } else if was_dashing {
+        velocity.current.x = velocity
+            .current
+            .x
+            .clamp(-physics.max_speed, physics.max_speed);
+    }
+}
+
+/// Fading copy of the player sprite left behind while dashing.
+#[derive(Component)]
+pub(super) struct Ghost(Timer);
+
+pub(super) fn spawn_ghosts(
+    mut commands: Commands,
+    player: Query<(&Transform, &Sprite, &Velocity), With<Player>>,
+) {
+    let Ok((transform, sprite, velocity)) = player.get_single() else {
+        return;
+    };
+    if !velocity.dashing {
+        return;
+    }
+    let mut ghost = sprite.clone();
+    ghost.color = ghost.color.with_alpha(0.5);
+    commands.spawn((
+        ghost,
+        Transform {
+            translation: transform.translation.with_z(transform.translation.z - 0.1),
+            ..*transform
+        },
+        StateScoped(GameState::Game),
+        Ghost(Timer::from_seconds(0.25, TimerMode::Once)),
+    ));
+}
+
+pub(super) fn fade_ghosts(
+    mut commands: Commands,
+    mut ghosts: Query<(Entity, &mut Sprite, &mut Ghost)>,
+    time: Res<Time>,
+) {
+    for (entity, mut sprite, mut ghost) in &mut ghosts {
+        ghost.0.tick(time.delta());
+        if ghost.0.finished() {
+            commands.entity(entity).despawn();
+        } else {
+            sprite.color = sprite.color.with_alpha(0.5 * ghost.0.fraction_remaining());
+        }
+    }
+}
@@ -1,5 +1,7 @@
 use bevy::prelude::*;
 
+use crate::level_loader::{Hazard, Item};
+
 use super::AudioTrigger;
 
 pub fn audio_plugin(app: &mut App) {
@@ -20,6 +22,75 @@ fn play_audio(
                     PlaybackSettings::DESPAWN,
                 ));
             }
+            AudioTrigger::DoubleJump => {
+                commands.spawn((
+                    AudioPlayer::<AudioSource>(sound_assets.double_jump.clone()),
+                    PlaybackSettings::DESPAWN,
+                ));
+            }
+            AudioTrigger::Dash => {
+                commands.spawn((
+                    AudioPlayer::<AudioSource>(sound_assets.jump.clone()),
+                    PlaybackSettings::DESPAWN.with_speed(0.6),
+                ));
+            }
+            AudioTrigger::Stomp => {
+                commands.spawn((
+                    AudioPlayer::<AudioSource>(sound_assets.jump.clone()),
+                    PlaybackSettings::DESPAWN.with_speed(2.0),
+                ));
+            }
+            AudioTrigger::Checkpoint => {
+                commands.spawn((
+                    AudioPlayer::<AudioSource>(sound_assets.checkpoint.clone()),
+                    PlaybackSettings::DESPAWN,
+                ));
+            }
+            AudioTrigger::Pickup(item) => {
+                let speed = match item {
+                    Item::Coin => 2.0,
+                    Item::Gem => 1.2,
+                };
+                commands.spawn((
+                    AudioPlayer::<AudioSource>(sound_assets.pickup.clone()),
+                    PlaybackSettings::DESPAWN.with_speed(speed),
+                ));
+            }
+            AudioTrigger::Key => {
+                commands.spawn((
+                    AudioPlayer::<AudioSource>(sound_assets.pickup.clone()),
+                    PlaybackSettings::DESPAWN.with_speed(1.5),
+                ));
+            }
+            AudioTrigger::DoorOpen => {
+                commands.spawn((
+                    AudioPlayer::<AudioSource>(sound_assets.checkpoint.clone()),
+                    PlaybackSettings::DESPAWN.with_speed(0.6),
+                ));
+            }
+            AudioTrigger::Hurt => {
+                commands.spawn((
+                    AudioPlayer::<AudioSource>(sound_assets.lost.clone()),
+                    PlaybackSettings::DESPAWN.with_speed(2.0),
+                ));
+            }
+            AudioTrigger::Hazard(hazard) => {
+                let sound = match hazard {
+                    Hazard::Spikes => &sound_assets.spikes,
+                    Hazard::Lava => &sound_assets.lava,
+                    Hazard::Saw => &sound_assets.saw,
+                };
+                commands.spawn((
+                    AudioPlayer::<AudioSource>(sound.clone()),
+                    PlaybackSettings::DESPAWN,
+                ));
+            }
+            AudioTrigger::Death => {
+                commands.spawn((
+                    AudioPlayer::<AudioSource>(sound_assets.lost.clone()),
+                    PlaybackSettings::DESPAWN,
+                ));
+            }
         }
     }
 }
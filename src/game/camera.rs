@@ -0,0 +1,102 @@
+use bevy::prelude::*;
+
+use crate::GameState;
+
+use super::{display_level, LevelBounds, Player};
+
+pub fn camera_plugin(app: &mut App) {
+    app.init_resource::<CameraSettings>()
+        .add_systems(OnEnter(GameState::Game), snap_camera.after(display_level))
+        .add_systems(OnExit(GameState::Game), reset_camera)
+        .add_systems(
+            PostUpdate,
+            follow_player
+                .before(TransformSystem::TransformPropagate)
+                .run_if(in_state(GameState::Game)),
+        );
+}
+
+#[derive(Resource)]
+pub struct CameraSettings {
+    /// Half extents of the area around the camera center where the player can move freely.
+    pub dead_zone: Vec2,
+    /// How fast the camera catches up with the player, higher is snappier.
+    pub smoothing: f32,
+}
+
+impl Default for CameraSettings {
+    fn default() -> Self {
+        Self {
+            dead_zone: Vec2::new(100.0, 60.0),
+            smoothing: 5.0,
+        }
+    }
+}
+
+fn dead_zone_target(camera: Vec2, player: Vec2, dead_zone: Vec2) -> Vec2 {
+    let offset = player - camera;
+    camera + offset - offset.clamp(-dead_zone, dead_zone)
+}
+
+fn clamp_to_bounds(position: Vec2, half_view: Vec2, bounds: Rect) -> Vec2 {
+    let clamp_axis = |position: f32, half_view: f32, min: f32, max: f32| {
+        if max - min <= half_view * 2.0 {
+            (min + max) / 2.0
+        } else {
+            position.clamp(min + half_view, max - half_view)
+        }
+    };
+    Vec2::new(
+        clamp_axis(position.x, half_view.x, bounds.min.x, bounds.max.x),
+        clamp_axis(position.y, half_view.y, bounds.min.y, bounds.max.y),
+    )
+}
+
+fn snap_camera(
+    mut camera: Query<(&mut Transform, &OrthographicProjection), With<Camera2d>>,
+    player: Query<&Transform, (With<Player>, Without<Camera2d>)>,
+    bounds: Res<LevelBounds>,
+) {
+    let Ok(player_transform) = player.get_single() else {
+        return;
+    };
+    let (mut camera_transform, projection) = camera.single_mut();
+    let position = clamp_to_bounds(
+        player_transform.translation.xy(),
+        projection.area.half_size(),
+        bounds.0,
+    );
+    camera_transform.translation = position.extend(camera_transform.translation.z);
+}
+
+fn follow_player(
+    mut camera: Query<(&mut Transform, &OrthographicProjection), With<Camera2d>>,
+    player: Query<&Transform, (With<Player>, Without<Camera2d>)>,
+    bounds: Res<LevelBounds>,
+    settings: Res<CameraSettings>,
+    time: Res<Time>,
+) {
+    let Ok(player_transform) = player.get_single() else {
+        return;
+    };
+    let (mut camera_transform, projection) = camera.single_mut();
+    let current = camera_transform.translation.xy();
+
+    let target = dead_zone_target(
+        current,
+        player_transform.translation.xy(),
+        settings.dead_zone,
+    );
+    let smoothed = current.lerp(
+        target,
+        1.0 - (-settings.smoothing * time.delta_secs()).exp(),
+    );
+    let position = clamp_to_bounds(smoothed, projection.area.half_size(), bounds.0);
+    camera_transform.translation = position.extend(camera_transform.translation.z);
+}
+
+fn reset_camera(mut camera: Query<&mut Transform, With<Camera2d>>) {
+    let mut camera_transform = camera.single_mut();
+    camera_transform.translation.x = 0.0;
+    camera_transform.translation.y = 0.0;
+}
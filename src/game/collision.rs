@@ -0,0 +1,158 @@
+use bevy::{math::bounding::Aabb2d, prelude::*};
+
+use super::TILE_SIZE;
+
+/// Distance under which two boxes are considered touching rather than overlapping.
+const SKIN: f32 = 0.001;
+/// How far a body can be below the top of a one-way platform and still land on it.
+const ONE_WAY_TOLERANCE: f32 = 1.0;
+
+/// Solid tiles of the level, indexed by their position in the level so that collision queries
+/// only look at the few cells around a body.
+#[derive(Resource)]
+pub struct TileGrid {
+    size: UVec2,
+    cells: Vec<Option<Entity>>,
+}
+
+impl TileGrid {
+    pub fn new(size: UVec2) -> Self {
+        Self {
+            size,
+            cells: vec![None; (size.x * size.y) as usize],
+        }
+    }
+
+    pub fn insert(&mut self, i: usize, j: usize, entity: Entity) {
+        self.cells[j * self.size.x as usize + i] = Some(entity);
+    }
+
+    /// Make the tile of `entity` passable.
+    pub fn remove(&mut self, entity: Entity) {
+        for cell in &mut self.cells {
+            if *cell == Some(entity) {
+                *cell = None;
+            }
+        }
+    }
+
+    /// Whether there is a solid tile at `point`.
+    pub fn is_solid_at(&self, point: Vec2) -> bool {
+        !self.solids_in(Aabb2d::new(point, Vec2::ZERO)).is_empty()
+    }
+
+    /// Solid tiles overlapping `area`, with their bounding box.
+    pub fn solids_in(&self, area: Aabb2d) -> Vec<(Entity, Aabb2d)> {
+        let cell = |x: f32, y: f32| {
+            IVec2::new(
+                ((x + TILE_SIZE / 2.0) / TILE_SIZE).floor() as i32,
+                ((-y + TILE_SIZE / 2.0) / TILE_SIZE).floor() as i32,
+            )
+        };
+        let min = cell(area.min.x, area.max.y).max(IVec2::ZERO);
+        let max = cell(area.max.x, area.min.y).min(self.size.as_ivec2() - IVec2::ONE);
+
+        let mut solids = vec![];
+        for j in min.y..=max.y {
+            for i in min.x..=max.x {
+                if let Some(entity) = self.cells[(j * self.size.x as i32 + i) as usize] {
+                    solids.push((
+                        entity,
+                        Aabb2d::new(
+                            Vec2::new(i as f32 * TILE_SIZE, -(j as f32) * TILE_SIZE),
+                            Vec2::splat(TILE_SIZE / 2.0),
+                        ),
+                    ));
+                }
+            }
+        }
+        solids
+    }
+}
+
+/// Area covered by `aabb` while moving by `delta`.
+pub fn swept_area(aabb: &Aabb2d, delta: Vec2) -> Aabb2d {
+    Aabb2d {
+        min: aabb.min + delta.min(Vec2::ZERO),
+        max: aabb.max + delta.max(Vec2::ZERO),
+    }
+}
+
+/// Whether a one-way platform at `solid` stops `aabb` moving by `delta`, which only happens when
+/// coming from above.
+pub fn lands_on_one_way(aabb: &Aabb2d, delta: Vec2, solid: &Aabb2d) -> bool {
+    delta.y <= 0.0 && aabb.min.y >= solid.max.y - ONE_WAY_TOLERANCE
+}
+
+/// A solid touched while moving.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Contact {
+    pub entity: Entity,
+    /// Normal of the touched surface, pointing away from the solid.
+    pub normal: Vec2,
+}
+
+/// Move `aabb` by `delta` against `solids`, first horizontally then vertically. On each axis the
+/// movement stops at the first solid in the way, so a fast body can't go through thin tiles.
+///
+/// Returns the movement actually done and the solids that stopped it. Solids already overlapping
+/// `aabb` are ignored so that a stuck body can move out of them.
+pub fn move_and_collide(
+    mut aabb: Aabb2d,
+    delta: Vec2,
+    solids: &[(Entity, Aabb2d)],
+) -> (Vec2, Vec<Contact>) {
+    let mut contacts = vec![];
+
+    let (dx, contact) = sweep_axis(&aabb, delta.x, Vec2::X, solids);
+    contacts.extend(contact);
+    aabb.min.x += dx;
+    aabb.max.x += dx;
+
+    let (dy, contact) = sweep_axis(&aabb, delta.y, Vec2::Y, solids);
+    contacts.extend(contact);
+
+    (Vec2::new(dx, dy), contacts)
+}
+
+fn sweep_axis(
+    aabb: &Aabb2d,
+    delta: f32,
+    axis: Vec2,
+    solids: &[(Entity, Aabb2d)],
+) -> (f32, Option<Contact>) {
+    if delta == 0.0 {
+        return (0.0, None);
+    }
+    let cross = axis.perp().abs();
+    let (min, max) = (aabb.min.dot(axis), aabb.max.dot(axis));
+    let (cross_min, cross_max) = (aabb.min.dot(cross), aabb.max.dot(cross));
+
+    let mut allowed = delta;
+    let mut contact = None;
+    for (entity, solid) in solids {
+        if solid.min.dot(cross) >= cross_max - SKIN || solid.max.dot(cross) <= cross_min + SKIN {
+            continue;
+        }
+        let distance = if delta > 0.0 {
+            solid.min.dot(axis) - max
+        } else {
+            solid.max.dot(axis) - min
+        };
+        if distance * delta.signum() < -SKIN {
+            // behind or overlapping
+            continue;
+        }
+        if distance.abs() <= allowed.abs() {
+            allowed = distance;
+            contact = Some(Contact {
+                entity: *entity,
+                normal: -axis * delta.signum(),
+            });
+        }
+    }
+    if contact.is_some() && allowed * delta.signum() < 0.0 {
+        allowed = 0.0;
+    }
+    (allowed, contact)
+}
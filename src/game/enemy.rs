@@ -0,0 +1,355 @@
+use std::time::Duration;
+
+use bevy::{math::bounding::Aabb2d, prelude::*, time::common_conditions::on_timer};
+
+use crate::{level_loader::EnemyKind, GameAssets, GameState};
+
+use super::{
+    collision::{lands_on_one_way, move_and_collide, swept_area, TileGrid},
+    platform::{move_platforms, platforms_in, MovingPlatform},
+    player::PlayerPhysics,
+    Contacts, LevelBounds, OneWay, Player, Velocity, SCALE, TILE_SIZE,
+};
+
+pub fn enemy_plugin(app: &mut App) {
+    app.add_systems(
+        FixedUpdate,
+        (
+            ((patrol, chase), enemy_movement)
+                .chain()
+                .after(move_platforms),
+            fly,
+            shoot,
+            move_projectiles,
+            squash_enemies,
+        )
+            .run_if(in_state(GameState::Game)),
+    )
+    .add_systems(
+        Update,
+        animate_enemies
+            .run_if(in_state(GameState::Game))
+            .run_if(on_timer(Duration::from_secs_f32(0.25))),
+    );
+}
+
+const PATROL_SPEED: f32 = 100.0;
+const CHASE_SPEED: f32 = 180.0;
+/// Horizontal distance under which a chaser notices the player.
+const CHASE_RANGE: f32 = 5.0 * TILE_SIZE;
+/// Horizontal and vertical extent of a flyer path around its starting point.
+const FLY_AMPLITUDE: Vec2 = Vec2::new(2.0 * TILE_SIZE, TILE_SIZE / 2.0);
+/// Angular speed along the flyer path, in radians per second.
+const FLY_SPEED: f32 = 1.5;
+const SHOOT_INTERVAL: f32 = 2.0;
+/// Distance under which a turret shoots at the player.
+const SHOOT_RANGE: f32 = 8.0 * TILE_SIZE;
+const PROJECTILE_SPEED: f32 = 300.0;
+pub(super) const PROJECTILE_SIZE: f32 = 12.0;
+
+/// Half size of the enemy collision box.
+const ENEMY_HALF_SIZE: Vec2 = Vec2::new(24.0, 16.0);
+
+#[derive(Component)]
+#[require(Velocity, Contacts)]
+pub(super) struct Enemy {
+    kind: EnemyKind,
+}
+
+impl Enemy {
+    /// Collision box, at the bottom of the tile for walking enemies and centered for flyers.
+    pub(super) fn aabb(&self, transform: &Transform) -> Aabb2d {
+        let offset = match self.kind {
+            EnemyKind::Flyer => 0.0,
+            _ => -TILE_SIZE / 2.0 + ENEMY_HALF_SIZE.y,
+        };
+        Aabb2d::new(
+            transform.translation.xy() + Vec2::new(0.0, offset),
+            ENEMY_HALF_SIZE,
+        )
+    }
+}
+
+/// Stomped by the player, despawned once its squash animation is done.
+#[derive(Component)]
+pub(super) struct Squashed;
+
+#[derive(Component)]
+struct SquashTimer(Timer);
+
+/// Walks back and forth, turning around at walls and ledges.
+#[derive(Component)]
+struct Patrol {
+    direction: f32,
+}
+
+/// Runs toward the player when in range, without leaving its platform.
+#[derive(Component)]
+struct Chase;
+
+/// Follows a sine path around `origin`, ignoring gravity and tiles.
+#[derive(Component)]
+struct Fly {
+    origin: Vec2,
+    phase: f32,
+}
+
+/// Shoots a projectile toward the player on a timer, while the player is in range.
+#[derive(Component)]
+struct Turret(Timer);
+
+/// Kills the player on contact, despawned when hitting a solid tile. Flies through one-way
+/// platforms.
+#[derive(Component)]
+pub(super) struct Projectile {
+    velocity: Vec2,
+}
+
+/// Animation frames in the enemy spritesheet. Sprites face left.
+fn walk_frames(kind: EnemyKind) -> [usize; 2] {
+    match kind {
+        EnemyKind::Patroller => [9, 17],
+        EnemyKind::Chaser => [10, 113],
+        EnemyKind::Flyer => [43, 59],
+        EnemyKind::Turret => [41, 57],
+    }
+}
+
+fn dead_frame(kind: EnemyKind) -> usize {
+    match kind {
+        EnemyKind::Patroller => 25,
+        EnemyKind::Chaser => 2,
+        EnemyKind::Flyer => 51,
+        EnemyKind::Turret => 49,
+    }
+}
+
+pub(super) fn spawn_enemy(
+    commands: &mut Commands,
+    kind: EnemyKind,
+    x: f32,
+    y: f32,
+    assets: &GameAssets,
+) {
+    let mut enemy = commands.spawn((
+        Sprite::from_atlas_image(
+            assets.enemy_image.clone(),
+            TextureAtlas {
+                layout: assets.enemy_layout.clone(),
+                index: walk_frames(kind)[0],
+            },
+        ),
+        Transform::from_xyz(x, y, 1.5).with_scale(Vec3::splat(SCALE)),
+        StateScoped(GameState::Game),
+        Enemy { kind },
+    ));
+    match kind {
+        EnemyKind::Patroller => enemy.insert(Patrol { direction: -1.0 }),
+        EnemyKind::Chaser => enemy.insert(Chase),
+        EnemyKind::Flyer => enemy.insert(Fly {
+            origin: Vec2::new(x, y),
+            phase: 0.0,
+        }),
+        EnemyKind::Turret => enemy.insert(Turret(Timer::from_seconds(
+            SHOOT_INTERVAL,
+            TimerMode::Repeating,
+        ))),
+    };
+}
+
+/// Whether there is ground, a tile or a moving platform, just past the edge of `aabb` in
+/// `direction`.
+fn ground_ahead(grid: &TileGrid, platforms: &Platforms, aabb: &Aabb2d, direction: f32) -> bool {
+    let front = if direction < 0.0 {
+        aabb.min.x - 1.0
+    } else {
+        aabb.max.x + 1.0
+    };
+    let point = Vec2::new(front, aabb.min.y - 1.0);
+    grid.is_solid_at(point) || !platforms_in(platforms, Aabb2d::new(point, Vec2::ZERO)).is_empty()
+}
+
+type Platforms<'w, 's, 'a> =
+    Query<'w, 's, (Entity, &'a Transform, &'a MovingPlatform), Without<Enemy>>;
+
+fn patrol(
+    mut enemies: Query<(
+        &Enemy,
+        &mut Patrol,
+        &mut Velocity,
+        &mut Sprite,
+        &Contacts,
+        &Transform,
+    )>,
+    grid: Res<TileGrid>,
+    platforms: Platforms,
+) {
+    for (enemy, mut patrol, mut velocity, mut sprite, contacts, transform) in &mut enemies {
+        let aabb = enemy.aabb(transform);
+        let wall = if patrol.direction < 0.0 {
+            contacts.wall_left()
+        } else {
+            contacts.wall_right()
+        };
+        let ledge =
+            contacts.on_ground() && !ground_ahead(&grid, &platforms, &aabb, patrol.direction);
+        if wall.is_some() || ledge {
+            patrol.direction = -patrol.direction;
+        }
+        velocity.current.x = patrol.direction * PATROL_SPEED;
+        sprite.flip_x = patrol.direction > 0.0;
+    }
+}
+
+fn chase(
+    mut enemies: Query<(&Enemy, &mut Velocity, &mut Sprite, &Contacts, &Transform), With<Chase>>,
+    player: Query<&Transform, With<Player>>,
+    grid: Res<TileGrid>,
+    platforms: Platforms,
+) {
+    let player = player.single().translation.xy();
+    for (enemy, mut velocity, mut sprite, contacts, transform) in &mut enemies {
+        let aabb = enemy.aabb(transform);
+        let offset = player - transform.translation.xy();
+        let direction = offset.x.signum();
+        velocity.current.x = if offset.x.abs() < CHASE_RANGE
+            && offset.y.abs() < TILE_SIZE
+            && (!contacts.on_ground() || ground_ahead(&grid, &platforms, &aabb, direction))
+        {
+            sprite.flip_x = direction > 0.0;
+            direction * CHASE_SPEED
+        } else {
+            0.0
+        };
+    }
+}
+
+fn fly(mut enemies: Query<(&mut Fly, &mut Transform, &mut Sprite)>, time: Res<Time>) {
+    for (mut fly, mut transform, mut sprite) in &mut enemies {
+        fly.phase += FLY_SPEED * time.delta_secs();
+        let offset = Vec2::new(fly.phase.sin(), (2.0 * fly.phase).sin()) * FLY_AMPLITUDE;
+        transform.translation = (fly.origin + offset).extend(transform.translation.z);
+        sprite.flip_x = fly.phase.cos() > 0.0;
+    }
+}
+
+fn shoot(
+    mut commands: Commands,
+    mut turrets: Query<(&mut Turret, &mut Sprite, &Transform)>,
+    player: Query<&Transform, With<Player>>,
+    time: Res<Time>,
+) {
+    let player = player.single().translation.xy();
+    for (mut turret, mut sprite, transform) in &mut turrets {
+        let offset = player - transform.translation.xy();
+        if offset.length() > SHOOT_RANGE {
+            continue;
+        }
+        let direction = offset.x.signum();
+        sprite.flip_x = direction > 0.0;
+        if !turret.0.tick(time.delta()).just_finished() {
+            continue;
+        }
+        commands.spawn((
+            Sprite::from_color(Color::srgb(1.0, 0.5, 0.1), Vec2::splat(PROJECTILE_SIZE)),
+            Transform::from_translation(transform.translation.with_z(1.6)),
+            StateScoped(GameState::Game),
+            Projectile {
+                velocity: Vec2::new(direction * PROJECTILE_SPEED, 0.0),
+            },
+        ));
+    }
+}
+
+fn move_projectiles(
+    mut commands: Commands,
+    mut projectiles: Query<(Entity, &Projectile, &mut Transform)>,
+    grid: Res<TileGrid>,
+    one_way: Query<(), With<OneWay>>,
+    bounds: Res<LevelBounds>,
+    time: Res<Time>,
+) {
+    for (entity, projectile, mut transform) in &mut projectiles {
+        transform.translation += (projectile.velocity * time.delta_secs()).extend(0.0);
+        let position = transform.translation.xy();
+        let hit = grid
+            .solids_in(Aabb2d::new(position, Vec2::ZERO))
+            .iter()
+            .any(|(solid, _)| !one_way.contains(*solid));
+        if hit || !bounds.0.contains(position) {
+            commands.entity(entity).despawn();
+        }
+    }
+}
+
+#[allow(clippy::type_complexity, clippy::too_many_arguments)]
+fn enemy_movement(
+    mut commands: Commands,
+    mut enemies: Query<
+        (Entity, &Enemy, &mut Transform, &mut Velocity, &mut Contacts),
+        Without<Fly>,
+    >,
+    grid: Res<TileGrid>,
+    platforms: Platforms,
+    one_way: Query<(), With<OneWay>>,
+    bounds: Res<LevelBounds>,
+    time: Res<Time>,
+    physics: Res<PlayerPhysics>,
+) {
+    for (entity, enemy, mut transform, mut velocity, mut contacts) in &mut enemies {
+        // Enemies fall like the player
+        velocity.current.y =
+            (velocity.current.y - physics.gravity * time.delta_secs()).max(-physics.max_fall_speed);
+
+        let aabb = enemy.aabb(&transform);
+        let delta = velocity.current * time.delta_secs();
+        let area = swept_area(&aabb, delta);
+        let mut solids = grid.solids_in(area);
+        solids.extend(platforms_in(&platforms, area));
+        // Enemies walk on one-way platforms like the player, but never drop through them
+        solids.retain(|(entity, solid)| {
+            !one_way.contains(*entity) || lands_on_one_way(&aabb, delta, solid)
+        });
+        let (movement, hits) = move_and_collide(aabb, delta, &solids);
+        transform.translation += movement.extend(0.0);
+
+        contacts.hits = hits;
+        if contacts.on_ground() {
+            velocity.current.y = velocity.current.y.max(0.0);
+        }
+        if transform.translation.y < bounds.0.min.y {
+            commands.entity(entity).despawn();
+        }
+    }
+}
+
+fn squash_enemies(
+    mut commands: Commands,
+    mut squashed: Query<(Entity, &Enemy, &mut Sprite, &mut Transform), Added<Squashed>>,
+    mut timers: Query<(Entity, &mut SquashTimer)>,
+    time: Res<Time>,
+) {
+    for (entity, enemy, mut sprite, mut transform) in &mut squashed {
+        sprite.texture_atlas.as_mut().unwrap().index = dead_frame(enemy.kind);
+        // flatten the sprite towards the ground
+        transform.scale.y *= 0.5;
+        transform.translation.y -= TILE_SIZE / 4.0;
+        commands
+            .entity(entity)
+            .remove::<(Enemy, Patrol, Chase, Fly, Turret)>()
+            .insert(SquashTimer(Timer::from_seconds(0.4, TimerMode::Once)));
+    }
+    for (entity, mut timer) in &mut timers {
+        if timer.0.tick(time.delta()).finished() {
+            commands.entity(entity).despawn();
+        }
+    }
+}
+
+fn animate_enemies(mut enemies: Query<(&Enemy, &mut Sprite)>) {
+    for (enemy, mut sprite) in &mut enemies {
+        let [first, second] = walk_frames(enemy.kind);
+        let atlas = sprite.texture_atlas.as_mut().unwrap();
+        atlas.index = if atlas.index == first { second } else { first };
+    }
+}
@@ -0,0 +1,90 @@
+use bevy::{
+    math::bounding::{Aabb2d, IntersectsVolume},
+    prelude::*,
+};
+
+use crate::{level_loader::Hazard, GameAssets, GameState};
+
+use super::{
+    lives::{player_alive, Hurt},
+    player::player_aabb,
+    Player, SCALE, TILE_SIZE,
+};
+
+/// Rotation speed of the saws, in radians per second.
+const SAW_SPIN: f32 = 6.0;
+
+pub fn hazard_plugin(app: &mut App) {
+    app.add_systems(
+        FixedUpdate,
+        hazard_contact.run_if(in_state(GameState::Game).and(player_alive)),
+    )
+    .add_systems(Update, spin_saws.run_if(in_state(GameState::Game)));
+}
+
+/// Hurts the player on contact, through the same pipeline as enemies.
+#[derive(Component)]
+struct HazardTile {
+    kind: Hazard,
+    /// Dangerous part of the tile, which only covers the visible part of the sprite.
+    area: Aabb2d,
+}
+
+pub(super) fn spawn_hazard(
+    commands: &mut Commands,
+    kind: Hazard,
+    x: f32,
+    y: f32,
+    assets: &GameAssets,
+) {
+    let (image, layout, index) = match kind {
+        Hazard::Spikes => (&assets.tiles_image, &assets.tiles_layout, 121),
+        Hazard::Lava => (&assets.tiles_image, &assets.tiles_layout, 3),
+        // Neither the tile nor the item spritesheet has a saw
+        Hazard::Saw => (&assets.enemy_image, &assets.enemy_layout, 89),
+    };
+    let area = match kind {
+        Hazard::Spikes => Aabb2d::new(
+            Vec2::new(x, y - TILE_SIZE / 4.0),
+            Vec2::new(TILE_SIZE * 0.4, TILE_SIZE / 4.0),
+        ),
+        Hazard::Lava => Aabb2d::new(
+            Vec2::new(x, y - TILE_SIZE / 8.0),
+            Vec2::new(TILE_SIZE / 2.0, TILE_SIZE * 3.0 / 8.0),
+        ),
+        Hazard::Saw => Aabb2d::new(Vec2::new(x, y), Vec2::splat(TILE_SIZE * 0.4)),
+    };
+    commands.spawn((
+        Sprite::from_atlas_image(
+            image.clone(),
+            TextureAtlas {
+                layout: layout.clone(),
+                index,
+            },
+        ),
+        Transform::from_xyz(x, y, 1.0).with_scale(Vec3::splat(SCALE)),
+        StateScoped(GameState::Game),
+        HazardTile { kind, area },
+    ));
+}
+
+fn hazard_contact(
+    player: Query<&Transform, With<Player>>,
+    hazards: Query<&HazardTile>,
+    mut hurts: EventWriter<Hurt>,
+) {
+    let player_aabb = player_aabb(player.single());
+    for hazard in &hazards {
+        if player_aabb.intersects(&hazard.area) {
+            hurts.send(Hurt::Hazard(hazard.kind));
+        }
+    }
+}
+
+fn spin_saws(mut hazards: Query<(&HazardTile, &mut Transform)>, time: Res<Time>) {
+    for (hazard, mut transform) in &mut hazards {
+        if hazard.kind == Hazard::Saw {
+            transform.rotate_z(-SAW_SPIN * time.delta_secs());
+        }
+    }
+}
@@ -0,0 +1,129 @@
+use bevy::prelude::*;
+
+use crate::{
+    campaign::{LevelItems, Lives},
+    level_loader::KeyColor,
+    GameAssets, GameState,
+};
+
+use super::{display_level, Inventory, Player};
+
+/// HUD sprites.
+const HEART: usize = 16;
+const COIN: usize = 32;
+
+pub fn hud_plugin(app: &mut App) {
+    app.add_systems(OnEnter(GameState::Game), display_hud.after(display_level))
+        .add_systems(
+            Update,
+            (update_counters, update_keys).run_if(in_state(GameState::Game)),
+        );
+}
+
+#[derive(Component)]
+struct LivesText;
+
+#[derive(Component)]
+struct CoinsText;
+
+/// Holds one icon per key in the player inventory.
+#[derive(Component)]
+struct KeysRow;
+
+fn hud_icon(assets: &GameAssets, index: usize) -> impl Bundle {
+    (
+        ImageNode::from_atlas_image(
+            assets.hud_image.clone(),
+            TextureAtlas {
+                layout: assets.hud_layout.clone(),
+                index,
+            },
+        ),
+        Node {
+            width: Val::Px(40.0),
+            height: Val::Px(40.0),
+            ..default()
+        },
+    )
+}
+
+fn hud_text(marker: impl Component) -> impl Bundle {
+    (
+        Text::default(),
+        TextFont {
+            font_size: 30.0,
+            ..default()
+        },
+        Node {
+            margin: UiRect::right(Val::Px(20.0)),
+            ..default()
+        },
+        marker,
+    )
+}
+
+fn display_hud(mut commands: Commands, assets: Res<GameAssets>) {
+    commands
+        .spawn((
+            Node {
+                position_type: PositionType::Absolute,
+                top: Val::Px(10.0),
+                left: Val::Px(10.0),
+                align_items: AlignItems::Center,
+                column_gap: Val::Px(5.0),
+                ..default()
+            },
+            StateScoped(GameState::Game),
+        ))
+        .with_children(|p| {
+            p.spawn(hud_icon(&assets, HEART));
+            p.spawn(hud_text(LivesText));
+            p.spawn(hud_icon(&assets, COIN));
+            p.spawn(hud_text(CoinsText));
+            p.spawn((
+                Node {
+                    column_gap: Val::Px(5.0),
+                    ..default()
+                },
+                KeysRow,
+            ));
+        });
+}
+
+fn update_counters(
+    mut lives_text: Query<&mut Text, (With<LivesText>, Without<CoinsText>)>,
+    mut coins_text: Query<&mut Text, (With<CoinsText>, Without<LivesText>)>,
+    lives: Res<Lives>,
+    items: Res<LevelItems>,
+) {
+    for mut text in &mut lives_text {
+        text.0 = lives.0.to_string();
+    }
+    for mut text in &mut coins_text {
+        text.0 = items.coins.to_string();
+    }
+}
+
+fn update_keys(
+    mut commands: Commands,
+    inventory: Query<&Inventory, (With<Player>, Changed<Inventory>)>,
+    row: Query<Entity, With<KeysRow>>,
+    assets: Res<GameAssets>,
+) {
+    let (Ok(inventory), Ok(row)) = (inventory.get_single(), row.get_single()) else {
+        return;
+    };
+    commands
+        .entity(row)
+        .despawn_descendants()
+        .with_children(|p| {
+            for key in &inventory.keys {
+                let index = match key {
+                    KeyColor::Yellow => 50,
+                    KeyColor::Orange => 3,
+                    KeyColor::Blue => 35,
+                };
+                p.spawn(hud_icon(&assets, index));
+            }
+        });
+}
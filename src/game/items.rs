@@ -0,0 +1,82 @@
+use bevy::{
+    math::bounding::{Aabb2d, IntersectsVolume},
+    prelude::*,
+    sprite::Anchor,
+};
+
+use crate::{
+    campaign::{LevelItems, Score},
+    level_loader::Item,
+    GameState,
+};
+
+use super::{
+    display_level, lives::player_alive, player::player_aabb, AudioTrigger, Collectible, Player,
+    SCALE, TILE_SIZE,
+};
+
+const COIN_POINTS: u32 = 1;
+const GEM_POINTS: u32 = 10;
+
+pub fn items_plugin(app: &mut App) {
+    app.add_systems(OnEnter(GameState::Game), count_items.after(display_level))
+        .add_systems(
+            FixedUpdate,
+            collect_items.run_if(in_state(GameState::Game).and(player_alive)),
+        )
+        .add_systems(Update, animate_items.run_if(in_state(GameState::Game)));
+}
+
+fn count_items(mut level_items: ResMut<LevelItems>, items: Query<&Collectible>, score: Res<Score>) {
+    *level_items = LevelItems {
+        start_score: score.0,
+        ..default()
+    };
+    for Collectible(item) in &items {
+        match item {
+            Item::Coin => level_items.coins.total += 1,
+            Item::Gem => level_items.gems.total += 1,
+        }
+    }
+}
+
+fn collect_items(
+    mut commands: Commands,
+    player: Query<&Transform, With<Player>>,
+    items: Query<(Entity, &Transform, &Collectible)>,
+    mut level_items: ResMut<LevelItems>,
+    mut score: ResMut<Score>,
+    mut audio_triggers: EventWriter<AudioTrigger>,
+) {
+    let player_aabb = player_aabb(player.single());
+    for (entity, transform, Collectible(item)) in &items {
+        let item_aabb = Aabb2d::new(transform.translation.xy(), Vec2::splat(TILE_SIZE / 4.0));
+        if !player_aabb.intersects(&item_aabb) {
+            continue;
+        }
+        match item {
+            Item::Coin => {
+                level_items.coins.collected += 1;
+                score.0 += COIN_POINTS;
+            }
+            Item::Gem => {
+                level_items.gems.collected += 1;
+                score.0 += GEM_POINTS;
+            }
+        }
+        audio_triggers.send(AudioTrigger::Pickup(*item));
+        commands.entity(entity).despawn();
+    }
+}
+
+/// Coins spin and gems float. Only the sprite changes, not the pickup area.
+fn animate_items(mut items: Query<(&Collectible, &mut Transform, &mut Sprite)>, time: Res<Time>) {
+    for (Collectible(item), mut transform, mut sprite) in &mut items {
+        // Offset by position so neighbouring items aren't in sync
+        let t = time.elapsed_secs() * 3.0 + transform.translation.x / TILE_SIZE;
+        match item {
+            Item::Coin => transform.scale.x = SCALE * t.cos(),
+            Item::Gem => sprite.anchor = Anchor::Custom(Vec2::new(0.0, t.sin() * 0.1)),
+        }
+    }
+}
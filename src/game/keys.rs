@@ -0,0 +1,92 @@
+use bevy::{
+    math::bounding::{Aabb2d, IntersectsVolume},
+    prelude::*,
+};
+
+use crate::GameState;
+
+use super::{
+    collision::TileGrid, lives::player_alive, player::player_aabb, AudioTrigger, Contacts, Door,
+    Inventory, KeyPickup, Player, SCALE, TILE_SIZE,
+};
+
+/// Duration of the door opening animation, in seconds.
+const OPENING_DURATION: f32 = 0.4;
+
+pub fn keys_plugin(app: &mut App) {
+    app.add_systems(
+        FixedUpdate,
+        (collect_keys, open_doors).run_if(in_state(GameState::Game).and(player_alive)),
+    )
+    .add_systems(Update, sink_doors.run_if(in_state(GameState::Game)));
+}
+
+/// An opened door sinking into the floor before disappearing.
+#[derive(Component)]
+struct Opening {
+    timer: Timer,
+    origin: f32,
+}
+
+fn collect_keys(
+    mut commands: Commands,
+    mut player: Query<(&Transform, &mut Inventory), With<Player>>,
+    keys: Query<(Entity, &Transform, &KeyPickup)>,
+    mut audio_triggers: EventWriter<AudioTrigger>,
+) {
+    let (player_transform, mut inventory) = player.single_mut();
+    let player_aabb = player_aabb(player_transform);
+    for (key, transform, KeyPickup(color)) in &keys {
+        let key_aabb = Aabb2d::new(transform.translation.xy(), Vec2::splat(TILE_SIZE / 4.0));
+        if player_aabb.intersects(&key_aabb) {
+            inventory.keys.push(*color);
+            audio_triggers.send(AudioTrigger::Key);
+            commands.entity(key).despawn();
+        }
+    }
+}
+
+/// Touching a door with a key of its colour uses the key and opens the door.
+fn open_doors(
+    mut commands: Commands,
+    mut player: Query<(&Contacts, &mut Inventory), With<Player>>,
+    doors: Query<(&Door, &Transform)>,
+    mut grid: ResMut<TileGrid>,
+    mut audio_triggers: EventWriter<AudioTrigger>,
+) {
+    let (contacts, mut inventory) = player.single_mut();
+    for contact in &contacts.hits {
+        let Ok((Door(color), transform)) = doors.get(contact.entity) else {
+            continue;
+        };
+        let Some(key) = inventory.keys.iter().position(|key| key == color) else {
+            continue;
+        };
+        inventory.keys.remove(key);
+        grid.remove(contact.entity);
+        audio_triggers.send(AudioTrigger::DoorOpen);
+        commands
+            .entity(contact.entity)
+            .remove::<Door>()
+            .insert(Opening {
+                timer: Timer::from_seconds(OPENING_DURATION, TimerMode::Once),
+                origin: transform.translation.y,
+            });
+    }
+}
+
+fn sink_doors(
+    mut commands: Commands,
+    mut doors: Query<(Entity, &mut Opening, &mut Transform)>,
+    time: Res<Time>,
+) {
+    for (entity, mut opening, mut transform) in &mut doors {
+        let progress = opening.timer.tick(time.delta()).fraction();
+        // Keep the bottom edge in place while shrinking
+        transform.scale.y = SCALE * (1.0 - progress);
+        transform.translation.y = opening.origin - TILE_SIZE / 2.0 * progress;
+        if opening.timer.finished() {
+            commands.entity(entity).despawn();
+        }
+    }
+}
@@ -0,0 +1,307 @@
+use bevy::{
+    math::bounding::{Aabb2d, IntersectsVolume},
+    prelude::*,
+};
+
+use crate::{
+    campaign::{CurrentLevel, Lives},
+    level_loader::{Campaign, Hazard, Level, LoadedCampaign},
+    GameState,
+};
+
+use super::{
+    display_level,
+    player::{player_aabb, PlayerPhysics},
+    standing_position, AudioTrigger, Checkpoint, Contacts, Health, Player, SpawnPoint, Velocity,
+    CHECKPOINT_ACTIVE, SCALE, TILE_SIZE,
+};
+
+/// Duration of the death animation, in seconds.
+const DEATH_DURATION: f32 = 1.0;
+/// Upward speed the player is thrown at when dying.
+const DEATH_BOUNCE: f32 = 700.0;
+/// Rotation speed of the death animation, in radians per second.
+const DEATH_SPIN: f32 = 8.0;
+/// Time the player can't be hurt after a non fatal hit, in seconds.
+const INVULNERABILITY: f32 = 1.5;
+/// Upward speed given by a non fatal hit.
+const HURT_BOUNCE: f32 = 400.0;
+/// Sinking speed when burning in lava.
+const BURN_SINK: f32 = 40.0;
+/// Rotation speed when caught in a saw, in radians per second.
+const SHRED_SPIN: f32 = 24.0;
+
+pub fn lives_plugin(app: &mut App) {
+    app.add_event::<Hurt>()
+        .add_systems(
+            OnEnter(GameState::Game),
+            set_level_health.after(display_level),
+        )
+        .add_systems(
+            FixedUpdate,
+            (
+                (take_damage, dying, invulnerability).chain(),
+                activate_checkpoints.run_if(player_alive),
+            )
+                .run_if(in_state(GameState::Game)),
+        );
+}
+
+/// Something hit the player.
+#[derive(Event, Clone, Copy)]
+pub(super) enum Hurt {
+    /// Takes one hit point.
+    Hit,
+    /// Kills the player whatever their health.
+    Fall,
+    /// Pushed against a wall by a moving platform, kills the player whatever their health.
+    Crush,
+    Hazard(Hazard),
+}
+
+impl Hurt {
+    fn is_lethal(self) -> bool {
+        matches!(self, Hurt::Fall | Hurt::Crush | Hurt::Hazard(Hazard::Lava))
+    }
+
+    fn sound(self, fatal: bool) -> AudioTrigger {
+        match self {
+            Hurt::Hazard(hazard) => AudioTrigger::Hazard(hazard),
+            _ if fatal => AudioTrigger::Death,
+            _ => AudioTrigger::Hurt,
+        }
+    }
+}
+
+/// The player lost a life and is playing the death animation.
+#[derive(Component)]
+pub(super) struct Dying {
+    timer: Timer,
+    effect: DeathEffect,
+}
+
+enum DeathEffect {
+    /// Jump out of the level spinning.
+    Spin,
+    /// Sink while fading out.
+    Burn,
+    /// Spin in place while shrinking.
+    Shred,
+}
+
+/// The player was hit recently and can't be hurt again yet.
+#[derive(Component)]
+struct Invulnerable(Timer);
+
+/// Run condition for the player systems, which stop during the death animation.
+pub(super) fn player_alive(player: Query<(), (With<Player>, Without<Dying>)>) -> bool {
+    !player.is_empty()
+}
+
+fn set_level_health(
+    mut commands: Commands,
+    player: Query<Entity, With<Player>>,
+    campaign: Res<LoadedCampaign>,
+    current_level: Res<CurrentLevel>,
+    campaigns: Res<Assets<Campaign>>,
+    levels: Res<Assets<Level>>,
+) {
+    let campaign = campaigns.get(&campaign.campaign).unwrap();
+    let level = levels.get(&campaign.levels[current_level.0].level).unwrap();
+    if let Some(health) = level.health {
+        for player in &player {
+            commands.entity(player).insert(Health::new(health));
+        }
+    }
+}
+
+#[allow(clippy::type_complexity)]
+fn take_damage(
+    mut commands: Commands,
+    mut hurts: EventReader<Hurt>,
+    mut player: Query<
+        (
+            Entity,
+            &mut Velocity,
+            &mut Sprite,
+            Option<&mut Health>,
+            Has<Invulnerable>,
+        ),
+        (With<Player>, Without<Dying>),
+    >,
+    mut lives: ResMut<Lives>,
+    mut audio_triggers: EventWriter<AudioTrigger>,
+) {
+    // Several hits can land during the same tick, only the worst one counts
+    let Some(hurt) = hurts.read().copied().max_by_key(|hurt| hurt.is_lethal()) else {
+        return;
+    };
+    let Ok((entity, mut velocity, mut sprite, health, invulnerable)) = player.get_single_mut()
+    else {
+        return;
+    };
+    let lethal = hurt.is_lethal();
+    if !lethal && invulnerable {
+        return;
+    }
+
+    if !lethal {
+        if let Some(mut health) = health {
+            health.current = health.current.saturating_sub(1);
+            if health.current > 0 {
+                audio_triggers.send(hurt.sound(false));
+                velocity.current.y = HURT_BOUNCE;
+                velocity.jumping = true;
+                commands
+                    .entity(entity)
+                    .insert(Invulnerable(Timer::from_seconds(
+                        INVULNERABILITY,
+                        TimerMode::Once,
+                    )));
+                return;
+            }
+        }
+    }
+
+    audio_triggers.send(hurt.sound(true));
+    lives.0 = lives.0.saturating_sub(1);
+    let effect = match hurt {
+        Hurt::Hazard(Hazard::Lava) => {
+            sprite.color = Color::srgb(1.0, 0.4, 0.1);
+            velocity.current = Vec2::new(0.0, -BURN_SINK);
+            DeathEffect::Burn
+        }
+        Hurt::Hazard(Hazard::Saw) => {
+            sprite.color = Color::WHITE;
+            DeathEffect::Shred
+        }
+        Hurt::Hazard(_) => {
+            sprite.color = Color::srgb(1.0, 0.3, 0.3);
+            velocity.current = Vec2::new(0.0, DEATH_BOUNCE);
+            DeathEffect::Spin
+        }
+        Hurt::Hit | Hurt::Fall | Hurt::Crush => {
+            sprite.color = Color::WHITE;
+            velocity.current = Vec2::new(0.0, DEATH_BOUNCE);
+            DeathEffect::Spin
+        }
+    };
+    commands
+        .entity(entity)
+        .remove::<Invulnerable>()
+        .insert(Dying {
+            timer: Timer::from_seconds(DEATH_DURATION, TimerMode::Once),
+            effect,
+        });
+}
+
+/// Play the death effect, then respawn or end the game.
+#[allow(clippy::type_complexity)]
+fn dying(
+    mut commands: Commands,
+    mut player: Query<
+        (
+            Entity,
+            &mut Dying,
+            &mut Transform,
+            &mut Velocity,
+            &mut Contacts,
+            &mut Sprite,
+            Option<&mut Health>,
+            &SpawnPoint,
+        ),
+        With<Player>,
+    >,
+    lives: Res<Lives>,
+    time: Res<Time>,
+    physics: Res<PlayerPhysics>,
+    mut next: ResMut<NextState<GameState>>,
+) {
+    let Ok((
+        entity,
+        mut dying,
+        mut transform,
+        mut velocity,
+        mut contacts,
+        mut sprite,
+        health,
+        spawn_point,
+    )) = player.get_single_mut()
+    else {
+        return;
+    };
+
+    let finished = dying.timer.tick(time.delta()).just_finished();
+    match dying.effect {
+        DeathEffect::Spin => {
+            velocity.current.y -= physics.gravity * time.delta_secs();
+            transform.rotate_z(DEATH_SPIN * time.delta_secs());
+        }
+        DeathEffect::Burn => sprite.color.set_alpha(1.0 - dying.timer.fraction()),
+        DeathEffect::Shred => {
+            // Caught in the saw, even if the hit landed while moving
+            velocity.current = Vec2::ZERO;
+            transform.rotate_z(-SHRED_SPIN * time.delta_secs());
+            transform.scale = Vec3::splat(SCALE * (1.0 - dying.timer.fraction()));
+        }
+    }
+    transform.translation += (velocity.current * time.delta_secs()).extend(0.0);
+
+    if !finished {
+        return;
+    }
+    if lives.0 == 0 {
+        next.set(GameState::GameOver);
+        return;
+    }
+
+    transform.translation = spawn_point.0;
+    transform.rotation = Quat::IDENTITY;
+    transform.scale = Vec3::splat(SCALE);
+    sprite.color = Color::WHITE;
+    *velocity = Velocity::default();
+    *contacts = Contacts::default();
+    if let Some(mut health) = health {
+        health.current = health.max;
+    }
+    commands.entity(entity).remove::<Dying>();
+}
+
+/// Blink the player while invulnerable.
+fn invulnerability(
+    mut commands: Commands,
+    mut player: Query<(Entity, &mut Invulnerable, &mut Sprite), With<Player>>,
+    time: Res<Time>,
+) {
+    let Ok((entity, mut invulnerable, mut sprite)) = player.get_single_mut() else {
+        return;
+    };
+    if invulnerable.0.tick(time.delta()).finished() {
+        sprite.color.set_alpha(1.0);
+        commands.entity(entity).remove::<Invulnerable>();
+    } else {
+        let visible = ((invulnerable.0.elapsed_secs() * 10.0) as u32).is_multiple_of(2);
+        sprite.color.set_alpha(if visible { 1.0 } else { 0.3 });
+    }
+}
+
+fn activate_checkpoints(
+    mut player: Query<(&Transform, &mut SpawnPoint), With<Player>>,
+    mut checkpoints: Query<(&Transform, &mut Checkpoint, &mut Sprite), Without<Player>>,
+    mut audio_triggers: EventWriter<AudioTrigger>,
+) {
+    let (transform, mut spawn_point) = player.single_mut();
+    let player_aabb = player_aabb(transform);
+    for (checkpoint_transform, mut checkpoint, mut sprite) in &mut checkpoints {
+        if checkpoint.active {
+            continue;
+        }
+        let position = checkpoint_transform.translation.xy();
+        if player_aabb.intersects(&Aabb2d::new(position, Vec2::splat(TILE_SIZE / 2.0))) {
+            checkpoint.active = true;
+            sprite.texture_atlas.as_mut().unwrap().index = CHECKPOINT_ACTIVE;
+            spawn_point.0 = standing_position(position.x, position.y);
+            audio_triggers.send(AudioTrigger::Checkpoint);
+        }
+    }
+}
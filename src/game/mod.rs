@@ -1,24 +1,50 @@
 use std::time::Duration;
 
 use bevy::{prelude::*, time::common_conditions::on_timer};
+use collision::{Contact, TileGrid};
 use flag::FlagMaterial;
 
 use crate::{
-    level_loader::{Level, LoadedLevel, Tile},
+    campaign::{CampaignProgress, CurrentLevel},
+    level_loader::{Ability, Campaign, Hazard, Item, KeyColor, Level, LoadedCampaign, Tile},
     GameAssets, GameState,
 };
 
+mod abilities;
 mod audio;
+mod camera;
+mod collision;
+mod enemy;
 mod flag;
+mod hazard;
+mod hud;
+mod items;
+mod keys;
+mod lives;
+mod pause;
+mod platform;
 mod player;
+#[cfg(test)]
+mod tests;
 
 const SCALE: f32 = 0.5;
+const TILE_SIZE: f32 = 128.0 * SCALE;
+/// Items sprites of the checkpoint flag, lowered and raised.
+const CHECKPOINT_INACTIVE: usize = 8;
+const CHECKPOINT_ACTIVE: usize = 2;
 
 pub fn game_plugin(app: &mut App) {
     app.add_plugins((
         player::player_plugin,
+        enemy::enemy_plugin,
         audio::audio_plugin,
-        flag::flag_plugin,
+        hazard::hazard_plugin,
+        hud::hud_plugin,
+        items::items_plugin,
+        keys::keys_plugin,
+        lives::lives_plugin,
+        pause::pause_plugin,
+        platform::platform_plugin,
     ))
     .add_systems(OnEnter(GameState::Game), display_level)
     .add_systems(
@@ -27,32 +53,171 @@ pub fn game_plugin(app: &mut App) {
     );
 }
 
+/// Camera and flag material, which need a window and a renderer.
+pub fn rendering_plugin(app: &mut App) {
+    app.add_plugins((camera::camera_plugin, flag::flag_plugin));
+}
+
 #[derive(Component)]
-#[require(IsOnGround, Velocity, AgainstWall)]
+#[require(Contacts, Velocity, JumpInput, Inventory)]
 struct Player;
 
+/// Keys picked up and not used yet.
 #[derive(Component, Default)]
-struct IsOnGround(f32);
+struct Inventory {
+    keys: Vec<KeyColor>,
+}
+
+/// Where the player comes back after losing a life.
+#[derive(Component)]
+struct SpawnPoint(Vec3);
+
+/// Hits the player can take before losing a life. Without it, any hit is fatal.
+#[derive(Component)]
+struct Health {
+    current: u32,
+    max: u32,
+}
 
+impl Health {
+    fn new(max: u32) -> Self {
+        Self { current: max, max }
+    }
+}
+
+/// Solids touched during the last movement.
 #[derive(Component, Default)]
-struct AgainstWall(bool, bool);
+struct Contacts {
+    hits: Vec<Contact>,
+    /// Last time the player was on the ground.
+    grounded_at: f32,
+}
+
+impl Contacts {
+    fn with_normal(&self, normal: Vec2) -> Option<Entity> {
+        self.hits
+            .iter()
+            .find(|contact| contact.normal == normal)
+            .map(|contact| contact.entity)
+    }
+
+    fn on_ground(&self) -> bool {
+        self.ground().is_some()
+    }
+
+    fn ground(&self) -> Option<Entity> {
+        self.with_normal(Vec2::Y)
+    }
+
+    fn ceiling(&self) -> Option<Entity> {
+        self.with_normal(Vec2::NEG_Y)
+    }
+
+    fn wall_left(&self) -> Option<Entity> {
+        self.with_normal(Vec2::X)
+    }
+
+    fn wall_right(&self) -> Option<Entity> {
+        self.with_normal(Vec2::NEG_X)
+    }
+}
 
 #[derive(Component, Default)]
 struct Velocity {
-    current: f32,
+    current: Vec2,
     target: f32,
-    jumping: f32,
+    jumping: bool,
+    /// Set during a dash, which ignores gravity and horizontal acceleration.
+    dashing: bool,
+    /// Set while pressing down, to fall through one-way platforms.
+    dropping: bool,
 }
 
+/// State of the jump button, kept between fixed ticks.
+#[derive(Component, Default)]
+struct JumpInput {
+    held: bool,
+    /// Time of the last jump press not consumed by a jump yet, so that it can still trigger one if
+    /// the player lands within the jump buffer window.
+    buffered_at: Option<f32>,
+}
+
+impl JumpInput {
+    /// Consume a jump press made less than `window` seconds ago.
+    fn take_buffered(&mut self, now: f32, window: f32) -> bool {
+        let buffered = self
+            .buffered_at
+            .is_some_and(|pressed_at| now - pressed_at <= window);
+        if buffered {
+            self.buffered_at = None;
+        }
+        buffered
+    }
+}
+
+/// Ability to jump once more while in the air.
+#[derive(Component, Default)]
+struct DoubleJump {
+    used: bool,
+}
+
+/// Ability to slide down walls and jump off them.
+#[derive(Component, Default)]
+struct WallJump;
+
+/// Ability to dash horizontally in the facing direction.
+#[derive(Component, Default)]
+struct Dash {
+    held: bool,
+    until: f32,
+    ready_at: f32,
+    direction: f32,
+}
+
+/// Grants an ability when the player touches it.
+#[derive(Component)]
+struct AbilityPickup(Ability);
+
+/// Goes to the inventory when the player touches it.
+#[derive(Component)]
+struct KeyPickup(KeyColor);
+
+/// Solid tile opened by touching it with a key of the same colour.
+#[derive(Component)]
+struct Door(KeyColor);
+
+/// Collectible adding to the score when the player touches it.
+#[derive(Component)]
+struct Collectible(Item);
+
 #[derive(Component)]
 struct Ground;
 
+/// Only solid for the player landing on it from above.
+#[derive(Component)]
+struct OneWay;
+
+/// World space area covered by the level tiles.
+#[derive(Resource)]
+struct LevelBounds(Rect);
+
 #[derive(Component)]
 struct Flag;
 
 #[derive(Event)]
 struct ReachedFlag;
 
+/// Becomes the respawn point once touched, for the rest of the level attempt.
+#[derive(Component, Default)]
+struct Checkpoint {
+    active: bool,
+}
+
+/// Position of the player standing on the tile at `x`, `y`.
+fn standing_position(x: f32, y: f32) -> Vec3 {
+    Vec3::new(x, y + 256.0 / 4.0 * SCALE, 2.0)
+}
+
 fn ground_tile_index(line: &[Tile], i: usize) -> usize {
     match (
         i == 0 || !matches!(line.get(i - 1).unwrap_or(&Tile::Empty), Tile::Ground),
@@ -65,6 +230,7 @@ fn ground_tile_index(line: &[Tile], i: usize) -> usize {
     }
 }
 
+#[allow(clippy::too_many_arguments)]
 fn display_tile(
     commands: &mut Commands,
     tile: &Tile,
@@ -75,11 +241,11 @@ fn display_tile(
     assets: &GameAssets,
     meshes: &mut Assets<Mesh>,
     flag_materials: &mut Assets<FlagMaterial>,
-) {
+) -> Option<Entity> {
     match tile {
         Tile::Ground => {
             let index = ground_tile_index(line, i);
-            commands.spawn((
+            let ground = commands.spawn((
                 Sprite::from_atlas_image(
                     assets.ground_image.clone(),
                     TextureAtlas {
@@ -91,8 +257,25 @@ fn display_tile(
                 Ground,
                 StateScoped(GameState::Game),
             ));
+            return Some(ground.id());
+        }
+        Tile::OneWay => {
+            let one_way = commands.spawn((
+                Sprite::from_atlas_image(
+                    assets.tiles_image.clone(),
+                    TextureAtlas {
+                        layout: assets.tiles_layout.clone(),
+                        index: 4,
+                    },
+                ),
+                Transform::from_xyz(x, y, 0.0).with_scale(Vec3::splat(SCALE)),
+                OneWay,
+                StateScoped(GameState::Game),
+            ));
+            return Some(one_way.id());
         }
         Tile::Spawn => {
+            let position = standing_position(x, y);
             commands.spawn((
                 Sprite::from_atlas_image(
                     assets.player_image.clone(),
@@ -101,9 +284,10 @@ fn display_tile(
                         index: 0,
                     },
                 ),
-                Transform::from_xyz(x, y + 256.0 / 4.0 * SCALE, 2.0).with_scale(Vec3::splat(SCALE)),
+                Transform::from_translation(position).with_scale(Vec3::splat(SCALE)),
                 StateScoped(GameState::Game),
                 Player,
+                SpawnPoint(position),
             ));
         }
         Tile::Flag => {
@@ -121,27 +305,132 @@ fn display_tile(
                 ))
                 .observe(reached_flag);
         }
-        Tile::Empty => {}
+        Tile::Checkpoint => {
+            commands.spawn((
+                Sprite::from_atlas_image(
+                    assets.items_image.clone(),
+                    TextureAtlas {
+                        layout: assets.items_layout.clone(),
+                        index: CHECKPOINT_INACTIVE,
+                    },
+                ),
+                Transform::from_xyz(x, y, 1.0).with_scale(Vec3::splat(SCALE)),
+                StateScoped(GameState::Game),
+                Checkpoint::default(),
+            ));
+        }
+        Tile::Pickup(ability) => {
+            let mut sprite = Sprite::from_atlas_image(
+                assets.items_image.clone(),
+                TextureAtlas {
+                    layout: assets.items_layout.clone(),
+                    index: 9,
+                },
+            );
+            match ability {
+                Ability::DoubleJump => {}
+                Ability::WallJump => sprite.color = Color::srgb(0.4, 0.8, 1.0),
+                Ability::Dash => sprite.color = Color::srgb(1.0, 0.5, 0.3),
+            }
+            commands.spawn((
+                sprite,
+                Transform::from_xyz(x, y, 1.0).with_scale(Vec3::splat(SCALE)),
+                StateScoped(GameState::Game),
+                AbilityPickup(*ability),
+            ));
+        }
+        Tile::Item(item) => {
+            let index = match item {
+                Item::Coin => 23,
+                Item::Gem => 11,
+            };
+            commands.spawn((
+                Sprite::from_atlas_image(
+                    assets.items_image.clone(),
+                    TextureAtlas {
+                        layout: assets.items_layout.clone(),
+                        index,
+                    },
+                ),
+                Transform::from_xyz(x, y, 1.0).with_scale(Vec3::splat(SCALE)),
+                StateScoped(GameState::Game),
+                Collectible(*item),
+            ));
+        }
+        Tile::Key(color) => {
+            let index = match color {
+                KeyColor::Yellow => 15,
+                KeyColor::Orange => 21,
+                KeyColor::Blue => 10,
+            };
+            commands.spawn((
+                Sprite::from_atlas_image(
+                    assets.items_image.clone(),
+                    TextureAtlas {
+                        layout: assets.items_layout.clone(),
+                        index,
+                    },
+                ),
+                Transform::from_xyz(x, y, 1.0).with_scale(Vec3::splat(SCALE)),
+                StateScoped(GameState::Game),
+                KeyPickup(*color),
+            ));
+        }
+        Tile::Door(color) => {
+            let index = match color {
+                KeyColor::Yellow => 74,
+                KeyColor::Orange => 82,
+                KeyColor::Blue => 98,
+            };
+            let door = commands.spawn((
+                Sprite::from_atlas_image(
+                    assets.tiles_image.clone(),
+                    TextureAtlas {
+                        layout: assets.tiles_layout.clone(),
+                        index,
+                    },
+                ),
+                Transform::from_xyz(x, y, 0.0).with_scale(Vec3::splat(SCALE)),
+                StateScoped(GameState::Game),
+                Door(*color),
+            ));
+            return Some(door.id());
+        }
+        Tile::Enemy(kind) => enemy::spawn_enemy(commands, *kind, x, y, assets),
+        Tile::Hazard(kind) => hazard::spawn_hazard(commands, *kind, x, y, assets),
+        // Spawned by run with their path, see `platform::spawn_platforms`
+        Tile::Platform | Tile::Empty => {}
     }
+    None
 }
 
+#[allow(clippy::too_many_arguments)]
 fn display_level(
     mut commands: Commands,
     assets: Res<GameAssets>,
-    level: Res<LoadedLevel>,
+    campaign: Res<LoadedCampaign>,
+    current_level: Res<CurrentLevel>,
+    campaigns: Res<Assets<Campaign>>,
     levels: Res<Assets<Level>>,
     mut meshes: ResMut<Assets<Mesh>>,
     mut flag_materials: ResMut<Assets<FlagMaterial>>,
 ) {
-    let level = levels.get(&level.level).unwrap();
+    let campaign = campaigns.get(&campaign.campaign).unwrap();
+    let level = levels.get(&campaign.levels[current_level.0].level).unwrap();
+
+    let mut grid = TileGrid::new(level.size());
+    let size = level.size().as_vec2() * TILE_SIZE;
+    commands.insert_resource(LevelBounds(Rect::new(
+        -TILE_SIZE / 2.0,
+        TILE_SIZE / 2.0 - size.y,
+        size.x - TILE_SIZE / 2.0,
+        TILE_SIZE / 2.0,
+    )));
 
     for (j, line) in level.tiles.iter().enumerate() {
         for (i, tile) in line.iter().enumerate() {
-            let (x, y) = (
-                (i as f32 - 9.0) * 128.0 * SCALE,
-                -(j as f32 - 5.0) * 128.0 * SCALE,
-            );
-            display_tile(
+            let (x, y) = (i as f32 * TILE_SIZE, -(j as f32) * TILE_SIZE);
+            if let Some(solid) = display_tile(
                 &mut commands,
                 tile,
                 i,
@@ -151,9 +440,13 @@ fn display_level(
                 &assets,
                 meshes.as_mut(),
                 flag_materials.as_mut(),
-            );
+            ) {
+                grid.insert(i, j, solid);
+            }
         }
     }
+    commands.insert_resource(grid);
+    platform::spawn_platforms(&mut commands, level, &assets);
 }
 
 fn animate_level(
@@ -170,11 +463,39 @@ fn animate_level(
     }
 }
 
-fn reached_flag(_trigger: Trigger<ReachedFlag>, mut next: ResMut<NextState<GameState>>) {
-    next.set(GameState::Menu);
+fn reached_flag(
+    trigger: Trigger<ReachedFlag>,
+    mut commands: Commands,
+    mut next: ResMut<NextState<GameState>>,
+    mut current_level: ResMut<CurrentLevel>,
+    mut progress: ResMut<CampaignProgress>,
+    campaign: Res<LoadedCampaign>,
+    campaigns: Res<Assets<Campaign>>,
+) {
+    // The flag keeps being reached until the state changes, only advance once
+    commands.entity(trigger.entity()).remove::<Flag>();
+
+    progress.complete(current_level.0);
+    let campaign = campaigns.get(&campaign.campaign).unwrap();
+    if current_level.0 + 1 < campaign.levels.len() {
+        current_level.0 += 1;
+        next.set(GameState::LevelComplete);
+    } else {
+        next.set(GameState::CampaignComplete);
+    }
 }
 
 #[derive(Event)]
 enum AudioTrigger {
     Jump,
+    DoubleJump,
+    Dash,
+    Stomp,
+    Checkpoint,
+    Pickup(Item),
+    Key,
+    DoorOpen,
+    Hurt,
+    Hazard(Hazard),
+    Death,
 }
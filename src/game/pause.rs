@@ -0,0 +1,120 @@
+use bevy::{
+    input::gamepad::{GamepadConnection, GamepadConnectionEvent},
+    prelude::*,
+};
+
+use crate::{
+    input::Action,
+    menu::{spawn_button, ButtonActivated},
+    GameState,
+};
+
+pub fn pause_plugin(app: &mut App) {
+    app.add_systems(
+        Update,
+        (toggle_pause, pause_on_disconnect, pause_action).run_if(in_state(GameState::Game)),
+    )
+    .add_systems(OnExit(GameState::Game), unpause);
+}
+
+#[derive(Component)]
+struct PauseScreen;
+
+#[derive(Component)]
+enum PauseButton {
+    Resume,
+    Menu,
+}
+
+fn toggle_pause(
+    mut commands: Commands,
+    actions: Res<ButtonInput<Action>>,
+    mut time: ResMut<Time<Virtual>>,
+    pause_screen: Query<Entity, With<PauseScreen>>,
+) {
+    if !actions.just_pressed(Action::Pause) {
+        return;
+    }
+    if time.is_paused() {
+        resume(&mut commands, &mut time, &pause_screen);
+    } else {
+        pause(&mut commands, &mut time);
+    }
+}
+
+/// Pause when a gamepad is unplugged, so the player doesn't keep running without control.
+fn pause_on_disconnect(
+    mut commands: Commands,
+    mut connections: EventReader<GamepadConnectionEvent>,
+    mut time: ResMut<Time<Virtual>>,
+) {
+    let disconnected = connections
+        .read()
+        .any(|connection| connection.connection == GamepadConnection::Disconnected);
+    if disconnected && !time.is_paused() {
+        pause(&mut commands, &mut time);
+    }
+}
+
+fn pause_action(
+    mut commands: Commands,
+    mut activated: EventReader<ButtonActivated>,
+    buttons: Query<&PauseButton>,
+    mut time: ResMut<Time<Virtual>>,
+    pause_screen: Query<Entity, With<PauseScreen>>,
+    mut next: ResMut<NextState<GameState>>,
+) {
+    for ButtonActivated(entity) in activated.read() {
+        let Ok(button) = buttons.get(*entity) else {
+            continue;
+        };
+        match button {
+            PauseButton::Resume => resume(&mut commands, &mut time, &pause_screen),
+            PauseButton::Menu => next.set(GameState::Menu),
+        }
+    }
+}
+
+fn pause(commands: &mut Commands, time: &mut Time<Virtual>) {
+    time.pause();
+    commands
+        .spawn((
+            Node {
+                width: Val::Percent(100.0),
+                height: Val::Percent(100.0),
+                align_items: AlignItems::Center,
+                justify_content: JustifyContent::Center,
+                flex_direction: FlexDirection::Column,
+                ..default()
+            },
+            BackgroundColor(Color::srgba(0.0, 0.0, 0.0, 0.5)),
+            PauseScreen,
+            StateScoped(GameState::Game),
+        ))
+        .with_children(|p| {
+            p.spawn((
+                Text::new("Paused"),
+                TextFont {
+                    font_size: 80.0,
+                    ..default()
+                },
+            ));
+            spawn_button(p, "Resume", PauseButton::Resume);
+            spawn_button(p, "Menu", PauseButton::Menu);
+        });
+}
+
+fn resume(
+    commands: &mut Commands,
+    time: &mut Time<Virtual>,
+    pause_screen: &Query<Entity, With<PauseScreen>>,
+) {
+    time.unpause();
+    for entity in pause_screen {
+        commands.entity(entity).despawn_recursive();
+    }
+}
+
+fn unpause(mut time: ResMut<Time<Virtual>>) {
+    time.unpause();
+}
@@ -0,0 +1,204 @@
+use std::f32::consts::PI;
+
+use bevy::{
+    ecs::query::QueryFilter,
+    math::bounding::{Aabb2d, IntersectsVolume},
+    prelude::*,
+};
+
+use crate::{
+    level_loader::{Level, PlatformAxis},
+    GameAssets, GameState,
+};
+
+use super::{
+    collision::{lands_on_one_way, move_and_collide, swept_area, TileGrid},
+    enemy::{Enemy, Squashed},
+    lives::{Dying, Hurt},
+    player::player_aabb,
+    OneWay, Player, SCALE, TILE_SIZE,
+};
+
+/// Ground sprite of the platform tiles.
+const PLATFORM_TILE: usize = 24;
+/// Distance between the player feet and a platform top under which the player rides it.
+const RIDE_TOLERANCE: f32 = 1.0;
+
+pub fn platform_plugin(app: &mut App) {
+    app.add_systems(
+        FixedUpdate,
+        move_platforms.run_if(in_state(GameState::Game)),
+    );
+}
+
+/// Solid moving back and forth along a level platform path.
+#[derive(Component)]
+pub(super) struct MovingPlatform {
+    origin: Vec2,
+    direction: Vec2,
+    /// In pixels.
+    range: f32,
+    /// Average speed, in pixels per second.
+    speed: f32,
+    half_size: Vec2,
+    elapsed: f32,
+}
+
+impl MovingPlatform {
+    pub(super) fn aabb(&self, transform: &Transform) -> Aabb2d {
+        Aabb2d::new(transform.translation.xy(), self.half_size)
+    }
+
+    /// Whether `body` stands on top of the platform.
+    fn carries(&self, transform: &Transform, body: &Aabb2d) -> bool {
+        let aabb = self.aabb(transform);
+        body.min.x < aabb.max.x
+            && body.max.x > aabb.min.x
+            && (body.min.y - aabb.max.y).abs() < RIDE_TOLERANCE
+    }
+
+    /// Movement given to `body` when the platform moves by `delta` from `transform`: riders
+    /// follow it, and a body in its way is pushed out along `delta`.
+    fn displacement(&self, transform: &Transform, delta: Vec2, body: &Aabb2d) -> Vec2 {
+        if self.carries(transform, body) {
+            return delta;
+        }
+        let moved = Aabb2d::new(transform.translation.xy() + delta, self.half_size);
+        let overlap = body.max.min(moved.max) - body.min.max(moved.min);
+        if overlap.x <= 0.0 || overlap.y <= 0.0 {
+            return Vec2::ZERO;
+        }
+        let push = |delta: f32, back: f32, front: f32| {
+            if delta > 0.0 {
+                front
+            } else if delta < 0.0 {
+                back
+            } else {
+                0.0
+            }
+        };
+        Vec2::new(
+            push(delta.x, moved.min.x - body.max.x, moved.max.x - body.min.x),
+            push(delta.y, moved.min.y - body.max.y, moved.max.y - body.min.y),
+        )
+    }
+}
+
+pub(super) fn spawn_platforms(commands: &mut Commands, level: &Level, assets: &GameAssets) {
+    for ((j, i, length), path) in level.platform_runs().into_iter().zip(&level.platforms) {
+        let origin = Vec2::new(
+            (i as f32 + (length - 1) as f32 / 2.0) * TILE_SIZE,
+            -(j as f32) * TILE_SIZE,
+        );
+        commands
+            .spawn((
+                Transform::from_translation(origin.extend(0.0)),
+                Visibility::default(),
+                StateScoped(GameState::Game),
+                MovingPlatform {
+                    origin,
+                    direction: match path.axis {
+                        PlatformAxis::Horizontal => Vec2::X,
+                        PlatformAxis::Vertical => Vec2::Y,
+                    },
+                    range: path.range * TILE_SIZE,
+                    speed: path.speed * TILE_SIZE,
+                    half_size: Vec2::new(length as f32, 1.0) * TILE_SIZE / 2.0,
+                    elapsed: 0.0,
+                },
+            ))
+            .with_children(|p| {
+                for k in 0..length {
+                    p.spawn((
+                        Sprite::from_atlas_image(
+                            assets.ground_image.clone(),
+                            TextureAtlas {
+                                layout: assets.ground_layout.clone(),
+                                index: PLATFORM_TILE,
+                            },
+                        ),
+                        Transform::from_xyz(
+                            (k as f32 - (length - 1) as f32 / 2.0) * TILE_SIZE,
+                            0.0,
+                            0.0,
+                        )
+                        .with_scale(Vec3::splat(SCALE)),
+                    ));
+                }
+            });
+    }
+}
+
+/// Moving platforms overlapping `area`, with their bounding box.
+pub(super) fn platforms_in<F: QueryFilter>(
+    platforms: &Query<(Entity, &Transform, &MovingPlatform), F>,
+    area: Aabb2d,
+) -> Vec<(Entity, Aabb2d)> {
+    platforms
+        .iter()
+        .map(|(entity, transform, platform)| (entity, platform.aabb(transform)))
+        .filter(|(_, aabb)| aabb.intersects(&area))
+        .collect()
+}
+
+/// Part of `push` that `aabb` can do before hitting a solid tile. One-way platforms only stop
+/// bodies pushed down onto them.
+fn clamp_push(
+    grid: &TileGrid,
+    one_way: &Query<(), With<OneWay>>,
+    aabb: &Aabb2d,
+    push: Vec2,
+) -> Vec2 {
+    let mut solids = grid.solids_in(swept_area(aabb, push));
+    solids.retain(|(entity, solid)| {
+        !one_way.contains(*entity) || lands_on_one_way(aabb, push, solid)
+    });
+    move_and_collide(*aabb, push, &solids).0
+}
+
+/// Platforms ease in and out at the ends of their path, carrying the player and enemies standing
+/// on them and pushing away the ones in their way.
+///
+/// Riding is checked before moving, as a platform rising into the player would otherwise overlap
+/// it and stop blocking it. Bodies stop at solid tiles, and the ones pushed against a tile are
+/// crushed.
+#[allow(clippy::type_complexity, clippy::too_many_arguments)]
+pub(super) fn move_platforms(
+    mut commands: Commands,
+    mut platforms: Query<(&mut Transform, &mut MovingPlatform), (Without<Player>, Without<Enemy>)>,
+    mut player: Query<&mut Transform, (With<Player>, Without<Dying>)>,
+    mut enemies: Query<(Entity, &Enemy, &mut Transform), (Without<Player>, Without<Squashed>)>,
+    grid: Res<TileGrid>,
+    one_way: Query<(), With<OneWay>>,
+    mut hurts: EventWriter<Hurt>,
+    time: Res<Time>,
+) {
+    let mut player = player.get_single_mut().ok();
+    for (mut transform, mut platform) in &mut platforms {
+        platform.elapsed += time.delta_secs();
+        let frequency = PI * platform.speed / platform.range;
+        let offset = platform.range * (1.0 - (platform.elapsed * frequency).cos()) / 2.0;
+        let position = platform.origin + platform.direction * offset;
+        let delta = position - transform.translation.xy();
+
+        if let Some(player) = player.as_mut() {
+            let aabb = player_aabb(player);
+            let displacement = platform.displacement(&transform, delta, &aabb);
+            let movement = clamp_push(&grid, &one_way, &aabb, displacement);
+            player.translation += movement.extend(0.0);
+            if movement != displacement && !platform.carries(&transform, &aabb) {
+                hurts.send(Hurt::Crush);
+            }
+        }
+        for (entity, enemy, mut enemy_transform) in &mut enemies {
+            let aabb = enemy.aabb(&enemy_transform);
+            let displacement = platform.displacement(&transform, delta, &aabb);
+            let movement = clamp_push(&grid, &one_way, &aabb, displacement);
+            enemy_transform.translation += movement.extend(0.0);
+            if movement != displacement && !platform.carries(&transform, &aabb) {
+                commands.entity(entity).insert(Squashed);
+            }
+        }
+        transform.translation = position.extend(transform.translation.z);
+    }
+}
@@ -1,80 +1,217 @@
 use bevy::{
-    math::bounding::{Aabb2d, IntersectsVolume},
+    math::bounding::{Aabb2d, BoundingVolume, IntersectsVolume},
     prelude::*,
 };
 
-use crate::GameState;
+use crate::{input::Action, GameState};
 
 use super::{
-    flag::FlagMaterial, AgainstWall, AudioTrigger, Flag, Ground, IsOnGround, Player, ReachedFlag,
-    Velocity,
+    abilities::{
+        collect_pickups, dash, double_jump, fade_ghosts, grant_level_abilities, spawn_ghosts,
+        wall_jump, wall_slide,
+    },
+    collision::{lands_on_one_way, move_and_collide, swept_area, TileGrid},
+    display_level,
+    enemy::{Enemy, Projectile, Squashed, PROJECTILE_SIZE},
+    flag::FlagMaterial,
+    lives::{player_alive, Hurt},
+    platform::{move_platforms, platforms_in, MovingPlatform},
+    AudioTrigger, Contacts, Flag, JumpInput, LevelBounds, OneWay, Player, ReachedFlag, Velocity,
 };
 
 pub fn player_plugin(app: &mut App) {
-    app.add_systems(
-        FixedUpdate,
-        (
-            control_player,
-            on_ground,
-            moving,
-            player_animation,
-            death_by_fall,
-            gravity.after(on_ground),
-            near_flag,
+    app.init_resource::<PlayerPhysics>()
+        .add_systems(
+            OnEnter(GameState::Game),
+            grant_level_abilities.after(display_level),
         )
-            .run_if(in_state(GameState::Game)),
-    );
+        .add_systems(
+            FixedUpdate,
+            (
+                (
+                    control_player,
+                    wall_jump,
+                    double_jump,
+                    dash,
+                    gravity,
+                    wall_slide,
+                    moving,
+                    enemy_contact,
+                    spawn_ghosts,
+                )
+                    .chain()
+                    .after(move_platforms),
+                player_animation,
+                death_by_fall,
+                projectile_contact,
+                near_flag,
+                collect_pickups,
+            )
+                .run_if(in_state(GameState::Game).and(player_alive)),
+        )
+        .add_systems(Update, fade_ghosts.run_if(in_state(GameState::Game)));
+}
+
+/// Tuning of the player movement. Speeds are in pixels per second, accelerations in pixels per
+/// second squared.
+#[derive(Resource)]
+pub struct PlayerPhysics {
+    pub gravity: f32,
+    pub max_fall_speed: f32,
+    pub jump_impulse: f32,
+    pub max_speed: f32,
+    pub acceleration: f32,
+    /// Deceleration when there is no input.
+    pub friction: f32,
+    /// Fraction of the acceleration and friction applied while in the air.
+    pub air_control: f32,
+    /// Upward speed the jump is cut to when the button is released early.
+    pub jump_release_speed: f32,
+    /// How long a jump press is remembered before landing, in seconds.
+    pub jump_buffer: f32,
+    /// How long the player can still jump after leaving the ground, in seconds.
+    pub coyote_time: f32,
+    pub double_jump_impulse: f32,
+    /// Maximum fall speed while sliding down a wall.
+    pub wall_slide_speed: f32,
+    /// Horizontal speed away from the wall given by a wall jump.
+    pub wall_jump_push: f32,
+    pub dash_speed: f32,
+    /// Duration of a dash, in seconds.
+    pub dash_duration: f32,
+    /// Time between the start of two dashes, in seconds.
+    pub dash_cooldown: f32,
+    /// Upward speed given by stomping an enemy.
+    pub stomp_bounce: f32,
+}
+
+impl Default for PlayerPhysics {
+    fn default() -> Self {
+        Self {
+            gravity: 2000.0,
+            max_fall_speed: 900.0,
+            jump_impulse: 900.0,
+            max_speed: 320.0,
+            acceleration: 1200.0,
+            friction: 1600.0,
+            air_control: 0.6,
+            jump_release_speed: 300.0,
+            jump_buffer: 0.12,
+            coyote_time: 0.1,
+            double_jump_impulse: 750.0,
+            wall_slide_speed: 150.0,
+            wall_jump_push: 450.0,
+            dash_speed: 900.0,
+            dash_duration: 0.15,
+            dash_cooldown: 0.6,
+            stomp_bounce: 600.0,
+        }
+    }
 }
 
 fn control_player(
-    keyboard_input: Res<ButtonInput<KeyCode>>,
-    mut player: Query<(&mut Velocity, &IsOnGround), With<Player>>,
+    actions: Res<ButtonInput<Action>>,
+    mut player: Query<(&mut Velocity, &mut JumpInput, &Contacts), With<Player>>,
     time: Res<Time>,
+    physics: Res<PlayerPhysics>,
     mut audio_triggers: EventWriter<AudioTrigger>,
 ) {
-    let (mut velocity, is_on_ground) = player.single_mut();
-    if time.elapsed_secs() - is_on_ground.0 < 2.0 || velocity.jumping > 0.0 {
-        if keyboard_input.pressed(KeyCode::KeyA) {
-            velocity.target = -5.0;
-        } else if keyboard_input.pressed(KeyCode::KeyD) {
-            velocity.target = 5.0;
-        } else {
-            velocity.target = 0.0;
-        }
+    let (mut velocity, mut jump, contacts) = player.single_mut();
+    let now = time.elapsed_secs();
+
+    if actions.pressed(Action::MoveLeft) {
+        velocity.target = -physics.max_speed;
+    } else if actions.pressed(Action::MoveRight) {
+        velocity.target = physics.max_speed;
+    } else {
+        velocity.target = 0.0;
     }
-    if time.elapsed_secs() - is_on_ground.0 < 0.5 && keyboard_input.pressed(KeyCode::Space) {
-        if velocity.jumping == 0.0 {
-            audio_triggers.send(AudioTrigger::Jump);
-        }
-        velocity.jumping = 15.0;
+    velocity.dropping = actions.pressed(Action::MoveDown);
+
+    // Fixed ticks don't match frames, so detect presses here rather than with `just_pressed`
+    let held = actions.pressed(Action::Jump);
+    if held && !jump.held {
+        jump.buffered_at = Some(now);
+    }
+    jump.held = held;
+
+    // A dash overrides the vertical speed, so presses during it stay buffered until it ends
+    let can_jump =
+        !velocity.jumping && !velocity.dashing && now - contacts.grounded_at <= physics.coyote_time;
+    if can_jump && jump.take_buffered(now, physics.jump_buffer) {
+        audio_triggers.send(AudioTrigger::Jump);
+        velocity.jumping = true;
+        velocity.current.y = physics.jump_impulse;
+    }
+    if velocity.jumping && !held {
+        velocity.current.y = velocity.current.y.min(physics.jump_release_speed);
     }
 }
 
-fn on_ground(
-    mut player: Query<(&Transform, &mut IsOnGround, &mut AgainstWall), With<Player>>,
-    ground: Query<&Transform, (Without<Player>, With<Ground>)>,
+fn gravity(
+    mut player: Query<&mut Velocity, With<Player>>,
     time: Res<Time>,
-    #[cfg(feature = "debug")] mut gizmos: Gizmos,
+    physics: Res<PlayerPhysics>,
 ) {
-    let mut is_on_ground = false;
-    let mut is_against_wall = (false, false);
-    let (player_transform, mut player_on_ground, mut player_against_wall) = player.single_mut();
+    let mut velocity = player.single_mut();
+    if velocity.dashing {
+        return;
+    }
+
+    velocity.current.y =
+        (velocity.current.y - physics.gravity * time.delta_secs()).max(-physics.max_fall_speed);
+}
+
+fn move_towards(current: f32, target: f32, max_delta: f32) -> f32 {
+    current + (target - current).clamp(-max_delta, max_delta)
+}
 
-    let player_aabb = Aabb2d::new(
+pub(super) fn player_aabb(transform: &Transform) -> Aabb2d {
+    Aabb2d::new(
         Vec2::new(
-            player_transform.translation.x,
-            player_transform.translation.y - 128.0 / 4.0,
+            transform.translation.x,
+            transform.translation.y - 128.0 / 4.0,
         ),
         Vec2::new(
-            128.0 * player_transform.scale.x,
-            (256.0 * 5.0 / 8.0) * player_transform.scale.y,
+            128.0 * transform.scale.x,
+            (256.0 * 5.0 / 8.0) * transform.scale.y,
         ) / 2.0
             * 0.8,
-    );
+    )
+}
+
+#[allow(clippy::type_complexity)]
+fn moving(
+    mut player: Query<(&mut Transform, &mut Velocity, &mut Contacts), With<Player>>,
+    grid: Res<TileGrid>,
+    platforms: Query<(Entity, &Transform, &MovingPlatform), Without<Player>>,
+    one_way: Query<(), With<OneWay>>,
+    time: Res<Time>,
+    physics: Res<PlayerPhysics>,
+    #[cfg(feature = "debug")] mut gizmos: Gizmos,
+) {
+    let (mut player_transform, mut velocity, mut contacts) = player.single_mut();
+
+    if !velocity.dashing {
+        let mut rate = if velocity.target != 0.0 {
+            physics.acceleration
+        } else {
+            physics.friction
+        };
+        if !contacts.on_ground() {
+            rate *= physics.air_control;
+        }
+        velocity.current.x = move_towards(
+            velocity.current.x,
+            velocity.target,
+            rate * time.delta_secs(),
+        );
+    }
+
+    let player_aabb = player_aabb(&player_transform);
 
     #[cfg(feature = "debug")]
     {
-        use bevy::math::bounding::BoundingVolume;
         gizmos.rect_2d(
             player_aabb.center(),
             player_aabb.half_size() * 2.,
@@ -82,86 +219,47 @@ fn on_ground(
         );
     }
 
-    for ground_transform in &ground {
-        let ground_aabb = Aabb2d::new(
-            Vec2::new(
-                ground_transform.translation.x,
-                ground_transform.translation.y,
-            ),
-            Vec2::new(
-                128.0 * ground_transform.scale.x,
-                128.0 * ground_transform.scale.y,
-            ) / 2.0,
-        );
-
-        if ground_aabb.intersects(&player_aabb) {
-            if ground_transform.translation.y
-                > player_transform.translation.y - 256.0 / 4.0 * player_transform.scale.y - 2.0
-            {
-                if ground_transform.translation.x < player_transform.translation.x {
-                    is_against_wall.0 = true;
-                } else {
-                    is_against_wall.1 = true;
-                }
-            } else {
-                is_on_ground = true;
-            }
-        }
-    }
-    if is_on_ground {
-        player_on_ground.0 = time.elapsed_secs();
-    }
-    if is_against_wall.0 != player_against_wall.0 {
-        player_against_wall.0 = is_against_wall.0;
-    }
-    if is_against_wall.1 != player_against_wall.1 {
-        player_against_wall.1 = is_against_wall.1;
-    }
-}
+    let delta = velocity.current * time.delta_secs();
+    let area = swept_area(&player_aabb, delta);
+    let mut solids = grid.solids_in(area);
+    solids.extend(platforms_in(&platforms, area));
+    // One-way platforms only block a player coming from above and not dropping through them
+    solids.retain(|(entity, aabb)| {
+        !one_way.contains(*entity)
+            || (!velocity.dropping && lands_on_one_way(&player_aabb, delta, aabb))
+    });
 
-fn gravity(mut player: Query<(&mut Transform, &IsOnGround), With<Player>>, time: Res<Time>) {
-    let (mut player_transform, player_on_ground) = player.single_mut();
+    let (movement, hits) = move_and_collide(player_aabb, delta, &solids);
+    player_transform.translation += movement.extend(0.0);
 
-    if time.elapsed_secs() - player_on_ground.0 > 0.1 {
-        player_transform.translation.y -= 10.0;
+    contacts.hits = hits;
+    if contacts.on_ground() {
+        contacts.grounded_at = time.elapsed_secs();
+        velocity.current.y = velocity.current.y.max(0.0);
+        velocity.jumping = false;
     }
-}
-
-fn moving(mut player: Query<(&mut Transform, &mut Velocity, &AgainstWall), With<Player>>) {
-    let (mut player_transform, mut velocity, against_wall) = player.single_mut();
-
-    if velocity.jumping > 0.0 {
-        player_transform.translation.y += velocity.jumping;
-        velocity.jumping -= 0.5;
+    if contacts.ceiling().is_some() {
+        velocity.current.y = velocity.current.y.min(0.0);
     }
-
-    if velocity.current != 0.0 {
-        if against_wall.0 && velocity.current < 0.0 {
-            velocity.current = 0.0;
-        }
-        if against_wall.1 && velocity.current > 0.0 {
-            velocity.current = 0.0;
-        }
-        player_transform.translation.x += velocity.current;
+    if contacts.wall_left().is_some() {
+        velocity.current.x = velocity.current.x.max(0.0);
     }
-    if velocity.current != velocity.target {
-        velocity.current += (velocity.target - velocity.current) / 10.0;
-        if velocity.current.abs() < 0.1 {
-            velocity.current = 0.0;
-        }
+    if contacts.wall_right().is_some() {
+        velocity.current.x = velocity.current.x.min(0.0);
     }
 }
 
+#[allow(clippy::type_complexity)]
 fn player_animation(
-    mut player: Query<(&mut Sprite, &Velocity), Changed<Transform>>,
+    mut player: Query<(&mut Sprite, &Velocity), (With<Player>, Changed<Transform>)>,
     mut steps: Local<u32>,
 ) {
     if let Ok((mut sprite, velocity)) = player.get_single_mut() {
-        if velocity.jumping > 0.0 {
+        if velocity.jumping {
             sprite.texture_atlas.as_mut().unwrap().index = 35;
         } else {
             *steps += 1;
-            if *steps % 10 == 0 {
+            if steps.is_multiple_of(10) {
                 sprite.texture_atlas.as_mut().unwrap().index =
                     if sprite.texture_atlas.as_ref().unwrap().index == 0 {
                         7
@@ -170,21 +268,69 @@ fn player_animation(
                     };
             }
         }
-        if velocity.current < 0.0 {
+        if velocity.current.x < 0.0 {
             sprite.flip_x = true;
-        } else if velocity.current > 0.0 {
+        } else if velocity.current.x > 0.0 {
             sprite.flip_x = false;
         }
     }
 }
 
 fn death_by_fall(
-    mut next: ResMut<NextState<GameState>>,
     player_transform: Query<&Transform, With<Player>>,
+    bounds: Res<LevelBounds>,
+    mut hurts: EventWriter<Hurt>,
 ) {
     let player_transform = player_transform.single();
-    if player_transform.translation.y < -400.0 {
-        next.set(GameState::Menu);
+    if player_transform.translation.y < bounds.0.min.y {
+        hurts.send(Hurt::Fall);
+    }
+}
+
+/// Landing on an enemy squashes it and bounces the player, any other contact hurts the player.
+#[allow(clippy::type_complexity)]
+fn enemy_contact(
+    mut commands: Commands,
+    mut player: Query<(&Transform, &mut Velocity), With<Player>>,
+    enemies: Query<(Entity, &Enemy, &Transform), (Without<Squashed>, Without<Player>)>,
+    physics: Res<PlayerPhysics>,
+    mut hurts: EventWriter<Hurt>,
+    mut audio_triggers: EventWriter<AudioTrigger>,
+) {
+    let (transform, mut velocity) = player.single_mut();
+    let player_aabb = player_aabb(transform);
+    for (entity, enemy, enemy_transform) in &enemies {
+        let enemy_aabb = enemy.aabb(enemy_transform);
+        if !player_aabb.intersects(&enemy_aabb) {
+            continue;
+        }
+        if velocity.current.y < 0.0 && player_aabb.min.y > enemy_aabb.center().y {
+            audio_triggers.send(AudioTrigger::Stomp);
+            commands.entity(entity).insert(Squashed);
+            velocity.current.y = physics.stomp_bounce;
+            velocity.jumping = true;
+        } else {
+            hurts.send(Hurt::Hit);
+        }
+    }
+}
+
+fn projectile_contact(
+    mut commands: Commands,
+    player: Query<&Transform, With<Player>>,
+    projectiles: Query<(Entity, &Transform), With<Projectile>>,
+    mut hurts: EventWriter<Hurt>,
+) {
+    let player_aabb = player_aabb(player.single());
+    for (entity, transform) in &projectiles {
+        let projectile_aabb = Aabb2d::new(
+            transform.translation.xy(),
+            Vec2::splat(PROJECTILE_SIZE / 2.0),
+        );
+        if player_aabb.intersects(&projectile_aabb) {
+            commands.entity(entity).despawn();
+            hurts.send(Hurt::Hit);
+        }
     }
 }
 
@@ -0,0 +1,228 @@
+use super::*;
+use crate::game::player::PlayerPhysics;
+
+/// Highest point reached when pressing jump again at the top of a first jump.
+fn second_jump_height(level: &str) -> f32 {
+    let mut app = TestApp::new(level);
+    app.step(5);
+    let start = app.player_feet();
+
+    app.press(KeyCode::Space);
+    let mut previous = start;
+    for _ in 0..60 {
+        app.step(1);
+        let feet = app.player_feet();
+        if feet < previous {
+            break;
+        }
+        previous = feet;
+    }
+    app.release(KeyCode::Space);
+    app.step(1);
+    app.press(KeyCode::Space);
+    let mut highest = start;
+    for _ in 0..60 {
+        app.step(1);
+        highest = highest.max(app.player_feet());
+    }
+    highest - start
+}
+
+const OPEN_FIELD: &str = "⬜⬜⬜⬜⬜\n\
+                          ⬜⬜⬜⬜⬜\n\
+                          ⬜⬜⬜⬜⬜\n\
+                          ⬜⬜⬜⬜⬜\n\
+                          ⬜⬜⬜⬜⬜\n\
+                          ⬜⬜🙂⬜⬜\n\
+                          🟩🟩🟩🟩🟩\n";
+
+#[test]
+fn double_jump_is_enabled_by_the_level() {
+    let single = second_jump_height(OPEN_FIELD);
+    let double = second_jump_height(&format!("abilities = double_jump\n{OPEN_FIELD}"));
+
+    assert!(
+        double > single + TILE_SIZE,
+        "double jump {double} isn't higher than single jump {single}"
+    );
+}
+
+#[test]
+fn pickup_grants_double_jump() {
+    let mut app = TestApp::new(
+        "⬜⬜⬜⬜⬜\n\
+         ⬜🙂🪽⬜⬜\n\
+         🟩🟩🟩🟩🟩\n",
+    );
+    let has_double_jump = |app: &mut TestApp| {
+        app.world_mut()
+            .query_filtered::<(), (With<Player>, With<DoubleJump>)>()
+            .iter(app.world())
+            .count()
+            == 1
+    };
+    assert!(!has_double_jump(&mut app));
+
+    app.press(KeyCode::ArrowRight);
+    app.step(30);
+    assert!(has_double_jump(&mut app), "pickup wasn't collected");
+    let pickups = app
+        .world_mut()
+        .query::<&AbilityPickup>()
+        .iter(app.world())
+        .count();
+    assert_eq!(pickups, 0);
+}
+
+const WALL: &str = "abilities = wall_jump\n\
+                    ⬜⬜⬜⬜⬜🟩\n\
+                    ⬜⬜⬜⬜⬜🟩\n\
+                    ⬜⬜⬜⬜⬜🟩\n\
+                    ⬜⬜⬜⬜⬜🟩\n\
+                    ⬜⬜⬜⬜⬜🟩\n\
+                    ⬜⬜⬜⬜⬜🟩\n\
+                    ⬜⬜⬜🙂⬜🟩\n\
+                    🟩🟩🟩🟩🟩🟩\n";
+
+/// Jump against the wall on the right, and wait until sliding down it.
+fn slide_down_wall(app: &mut TestApp) {
+    app.step(5);
+    app.press(KeyCode::ArrowRight);
+    app.press(KeyCode::Space);
+    app.step(20);
+    app.release(KeyCode::Space);
+    for _ in 0..60 {
+        app.step(1);
+        if app.player_velocity().y < 0.0
+            && app
+                .player_contacts()
+                .iter()
+                .any(|c| c.normal == Vec2::NEG_X)
+        {
+            return;
+        }
+    }
+    panic!("player didn't reach the wall");
+}
+
+#[test]
+fn wall_slide_limits_fall_speed() {
+    let mut app = TestApp::new(WALL);
+    slide_down_wall(&mut app);
+
+    let before = app.player_position().y;
+    app.step(20);
+    let speed = (before - app.player_position().y)
+        / (20.0 * Time::<Fixed>::default().timestep().as_secs_f32());
+    let physics = PlayerPhysics::default();
+    assert!(
+        speed <= physics.wall_slide_speed + 0.1,
+        "sliding at {speed}"
+    );
+}
+
+#[test]
+fn wall_jump_pushes_away_from_the_wall() {
+    let mut app = TestApp::new(WALL);
+    slide_down_wall(&mut app);
+
+    let before = app.player_position();
+    app.press(KeyCode::Space);
+    app.step(10);
+    let after = app.player_position();
+    assert!(after.y > before.y, "player didn't jump off the wall");
+    assert!(after.x < before.x, "player didn't move away from the wall");
+}
+
+const DASH_LANE: &str = "abilities = dash\n\
+                         ⬜⬜⬜⬜⬜⬜⬜⬜⬜⬜🟩\n\
+                         ⬜⬜⬜⬜🙂⬜⬜⬜⬜⬜🟩\n\
+                         🟩🟩🟩🟩🟩🟩🟩🟩🟩🟩🟩\n";
+
+fn dash(app: &mut TestApp) {
+    app.press(KeyCode::ShiftLeft);
+    app.step(1);
+    app.release(KeyCode::ShiftLeft);
+}
+
+#[test]
+fn dash_moves_in_the_facing_direction() {
+    let mut app = TestApp::new(DASH_LANE);
+    app.press(KeyCode::ArrowLeft);
+    app.step(5);
+    app.release(KeyCode::ArrowLeft);
+    app.step(20);
+    let start = app.player_position();
+
+    dash(&mut app);
+    let ghosts = app
+        .world_mut()
+        .query::<&crate::game::abilities::Ghost>()
+        .iter(app.world())
+        .count();
+    assert!(ghosts > 0, "dash left no trail");
+    app.step(10);
+
+    let physics = PlayerPhysics::default();
+    let end = app.player_position();
+    assert!(
+        start.x - end.x > physics.dash_speed * physics.dash_duration * 0.8,
+        "dash was too short: {}",
+        start.x - end.x
+    );
+    assert_eq!(end.y, start.y);
+}
+
+#[test]
+fn dash_has_a_cooldown() {
+    let mut app = TestApp::new(DASH_LANE);
+    app.step(5);
+    let physics = PlayerPhysics::default();
+
+    dash(&mut app);
+    app.step(10);
+    dash(&mut app);
+    assert!(
+        app.player_velocity().x <= physics.max_speed,
+        "dashed again during the cooldown"
+    );
+
+    app.step(40);
+    dash(&mut app);
+    assert_eq!(app.player_velocity().x, physics.dash_speed);
+}
+
+#[test]
+fn jump_pressed_while_dashing_happens_after_the_dash() {
+    let mut app = TestApp::new(DASH_LANE);
+    app.step(5);
+    let start = app.player_position();
+
+    dash(&mut app);
+    app.step(4);
+    app.press(KeyCode::Space);
+    app.step(20);
+
+    assert!(
+        app.player_position().y > start.y + TILE_SIZE / 2.0,
+        "jump was swallowed by the dash"
+    );
+}
+
+#[test]
+fn dash_is_stopped_by_walls() {
+    let mut app = TestApp::new(DASH_LANE);
+    app.step(5);
+
+    for _ in 0..4 {
+        dash(&mut app);
+        app.step(40);
+    }
+
+    let right = app.player_position().x + 128.0 * SCALE / 2.0 * 0.8;
+    assert!(
+        (right - column_left(10)).abs() < 0.01,
+        "player at {right}, wall at {}",
+        column_left(10)
+    );
+}
@@ -0,0 +1,44 @@
+use std::time::{Duration, Instant};
+
+use super::*;
+
+fn flat_level(width: usize, height: usize) -> String {
+    let mut level = String::new();
+    for j in 0..height {
+        for i in 0..width {
+            level.push(match (i, j) {
+                (1, j) if j == height / 2 => '🙂',
+                (_, j) if j > height / 2 => '🟩',
+                _ => '⬜',
+            });
+        }
+        level.push('\n');
+    }
+    level
+}
+
+fn average_tick(app: &mut TestApp, ticks: u32) -> Duration {
+    let start = Instant::now();
+    app.step(ticks);
+    start.elapsed() / ticks
+}
+
+/// Run with `cargo test --release -- --ignored --nocapture tick_cost`
+#[test]
+#[ignore]
+fn bench_tick_cost_does_not_grow_with_level_size() {
+    let mut small = TestApp::new(&flat_level(20, 10));
+    let mut large = TestApp::new(&flat_level(1000, 100));
+    for app in [&mut small, &mut large] {
+        app.press(KeyCode::KeyD);
+        average_tick(app, 20);
+    }
+
+    let small = average_tick(&mut small, 500);
+    let large = average_tick(&mut large, 500);
+    println!("20x10 level: {small:?} per tick, 1000x100 level: {large:?} per tick");
+    assert!(
+        large < small * 2,
+        "tick cost grows with the level size: {small:?} -> {large:?}"
+    );
+}
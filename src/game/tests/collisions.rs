@@ -0,0 +1,117 @@
+use super::*;
+use crate::game::player::PlayerPhysics;
+
+#[test]
+fn jump_reaches_above_two_tiles() {
+    let mut app = TestApp::new(
+        "⬜⬜⬜⬜⬜\n\
+         ⬜⬜⬜⬜⬜\n\
+         ⬜⬜⬜⬜⬜\n\
+         ⬜⬜⬜⬜⬜\n\
+         ⬜⬜🙂⬜⬜\n\
+         🟩🟩🟩🟩🟩\n",
+    );
+    let start = app.player_feet();
+
+    app.press(KeyCode::Space);
+    let mut highest = start;
+    for _ in 0..60 {
+        app.step(1);
+        highest = highest.max(app.player_feet());
+    }
+
+    assert!(
+        highest - start > 2.0 * TILE_SIZE,
+        "jump is too low: {}",
+        highest - start
+    );
+    assert_eq!(app.player_feet(), start, "player didn't land");
+}
+
+#[test]
+fn jump_stops_at_ceiling() {
+    let mut app = TestApp::new(
+        "⬜⬜⬜⬜⬜\n\
+         🟩🟩🟩🟩🟩\n\
+         ⬜⬜⬜⬜⬜\n\
+         ⬜⬜🙂⬜⬜\n\
+         🟩🟩🟩🟩🟩\n",
+    );
+    // bottom edge of the ceiling row
+    let ceiling = row_top(2);
+    let start = app.player_position();
+
+    app.press(KeyCode::Space);
+    let mut highest = start.y;
+    for _ in 0..20 {
+        app.step(1);
+        highest = highest.max(app.player_position().y);
+    }
+    app.release(KeyCode::Space);
+    for _ in 0..60 {
+        app.step(1);
+        highest = highest.max(app.player_position().y);
+    }
+
+    // the top of the player's collision box is its translation
+    assert!(highest > start.y, "player didn't jump");
+    assert!(
+        highest <= ceiling + 0.01,
+        "player went through the ceiling: {highest} > {ceiling}"
+    );
+    assert_eq!(app.player_position(), start, "player didn't land");
+}
+
+#[test]
+fn fast_fall_does_not_tunnel() {
+    let mut app = TestApp::new(
+        "⬜🙂⬜\n\
+         ⬜⬜⬜\n\
+         ⬜⬜⬜\n\
+         ⬜⬜⬜\n\
+         🟩🟩🟩\n",
+    );
+    {
+        let mut physics = app.world_mut().resource_mut::<PlayerPhysics>();
+        physics.gravity = 100_000.0;
+        physics.max_fall_speed = 10_000.0;
+    }
+    // at full speed the player moves more than a tile per tick
+    app.step(30);
+
+    let feet = app.player_feet();
+    assert!(
+        (feet - row_top(4)).abs() < 0.01,
+        "player is not standing on the floor: {feet} != {}",
+        row_top(4)
+    );
+}
+
+#[test]
+fn walking_into_wall_stops_at_its_edge() {
+    let mut app = TestApp::new(
+        "⬜⬜⬜⬜⬜⬜\n\
+         ⬜🙂⬜⬜🟩⬜\n\
+         🟩🟩🟩🟩🟩🟩\n",
+    );
+
+    app.press(KeyCode::KeyD);
+    app.step(60);
+
+    let contacts = app.player_contacts();
+    assert!(
+        contacts.iter().any(|contact| contact.normal == Vec2::NEG_X),
+        "no wall contact: {contacts:?}"
+    );
+    assert!(
+        contacts.iter().any(|contact| contact.normal == Vec2::Y),
+        "no ground contact: {contacts:?}"
+    );
+    // right edge of the player collision box
+    let right = app.player_position().x + 128.0 * SCALE / 2.0 * 0.8;
+    assert!(
+        (right - column_left(4)).abs() < 0.01,
+        "player is not against the wall: {right} != {}",
+        column_left(4)
+    );
+}
@@ -0,0 +1,222 @@
+use bevy::math::bounding::{Aabb2d, BoundingVolume};
+
+use super::*;
+use crate::{
+    campaign::STARTING_LIVES,
+    game::enemy::{Enemy, Projectile, Squashed},
+};
+
+fn enemy_aabb_of(app: &mut TestApp) -> Aabb2d {
+    let (enemy, transform) = app
+        .world_mut()
+        .query::<(&Enemy, &Transform)>()
+        .single(app.world());
+    enemy.aabb(transform)
+}
+
+#[test]
+fn patroller_turns_at_walls_and_ledges() {
+    let mut app = TestApp::new(
+        "⬜⬜⬜⬜⬜⬜⬜⬜\n\
+         🙂⬜🟩⬜⬜👾⬜⬜\n\
+         🟩⬜🟩🟩🟩🟩🟩⬜\n",
+    );
+    let mut turns = 0;
+    let mut previous = enemy_aabb_of(&mut app).min.x;
+    let mut direction = 0.0;
+    for _ in 0..600 {
+        app.step(1);
+        let aabb = enemy_aabb_of(&mut app);
+        assert!(aabb.min.x >= column_left(3) - 0.01, "walked into the wall");
+        assert!(aabb.max.x <= column_left(7) + 1.0, "walked off the ledge");
+        assert!((aabb.min.y - row_top(2)).abs() < 0.01, "left the ground");
+
+        let new_direction = (aabb.min.x - previous).signum();
+        if new_direction != direction {
+            turns += 1;
+            direction = new_direction;
+        }
+        previous = aabb.min.x;
+    }
+    assert!(turns >= 4, "patroller turned {turns} times");
+}
+
+fn enemy_count(app: &mut TestApp) -> usize {
+    app.world_mut()
+        .query_filtered::<(), With<Enemy>>()
+        .iter(app.world())
+        .count()
+}
+
+#[test]
+fn touching_an_enemy_from_the_side_kills_the_player() {
+    let mut app = TestApp::new(
+        "⬜⬜⬜⬜⬜\n\
+         🙂⬜⬜👾⬜\n\
+         🟩🟩🟩🟩🟩\n",
+    );
+
+    app.step(120);
+
+    assert_eq!(app.lives(), STARTING_LIVES - 1);
+}
+
+#[test]
+fn landing_on_an_enemy_squashes_it() {
+    let mut app = TestApp::new(
+        "⬜⬜⬜\n\
+         ⬜🙂⬜\n\
+         ⬜⬜⬜\n\
+         🟩👾🟩\n\
+         🟩🟩🟩\n",
+    );
+
+    let mut bounced = false;
+    for _ in 0..60 {
+        app.step(1);
+        bounced |= app.player_velocity().y > 0.0;
+    }
+
+    assert_eq!(app.state(), GameState::Game);
+    assert!(bounced, "player didn't bounce off the enemy");
+    assert_eq!(enemy_count(&mut app), 0);
+    let squashed = app
+        .world_mut()
+        .query_filtered::<(), With<Squashed>>()
+        .iter(app.world())
+        .count();
+    assert_eq!(squashed, 0, "squashed enemy wasn't despawned");
+}
+
+#[test]
+fn chaser_runs_toward_a_close_player() {
+    let mut app = TestApp::new(
+        "⬜⬜⬜⬜⬜⬜⬜⬜⬜⬜⬜⬜⬜⬜\n\
+         🙂⬜⬜⬜🐭⬜⬜⬜⬜⬜⬜⬜⬜🐭\n\
+         🟩🟩🟩🟩🟩🟩🟩🟩🟩🟩🟩🟩🟩🟩\n",
+    );
+    let start = app
+        .world_mut()
+        .query_filtered::<&Transform, With<Enemy>>()
+        .iter(app.world())
+        .map(|transform| transform.translation.x)
+        .collect::<Vec<_>>();
+
+    app.step(10);
+
+    let end = app
+        .world_mut()
+        .query_filtered::<&Transform, With<Enemy>>()
+        .iter(app.world())
+        .map(|transform| transform.translation.x)
+        .collect::<Vec<_>>();
+    let moved = start
+        .iter()
+        .zip(&end)
+        .map(|(start, end)| end - start)
+        .collect::<Vec<_>>();
+    let (near, far) = if start[0] < start[1] { (0, 1) } else { (1, 0) };
+    assert!(moved[near] < 0.0, "close chaser didn't run to the player");
+    assert_eq!(moved[far], 0.0, "far chaser noticed the player");
+}
+
+#[test]
+fn flyer_ignores_gravity() {
+    let mut app = TestApp::new(
+        "⬜⬜⬜⬜⬜⬜⬜⬜\n\
+         ⬜⬜⬜⬜🐝⬜⬜⬜\n\
+         ⬜⬜⬜⬜⬜⬜⬜⬜\n\
+         🙂⬜⬜⬜⬜⬜⬜⬜\n\
+         🟩⬜⬜⬜⬜⬜⬜⬜\n",
+    );
+    let origin = Vec2::new(4.0 * TILE_SIZE, -TILE_SIZE);
+
+    let mut lowest = origin.y;
+    for _ in 0..300 {
+        app.step(1);
+        lowest = lowest.min(enemy_aabb_of(&mut app).center().y);
+    }
+    assert!(
+        lowest >= origin.y - TILE_SIZE / 2.0 - 0.01,
+        "flyer fell to {lowest}"
+    );
+    assert!(lowest < origin.y, "flyer didn't move");
+}
+
+#[test]
+fn turret_projectiles_kill_the_player() {
+    let mut app = TestApp::new(
+        "⬜⬜⬜⬜⬜⬜\n\
+         🙂⬜⬜⬜⬜🔫\n\
+         🟩🟩🟩🟩🟩🟩\n",
+    );
+
+    let mut fired = false;
+    for _ in 0..300 {
+        app.step(1);
+        fired |= app
+            .world_mut()
+            .query::<&Projectile>()
+            .iter(app.world())
+            .count()
+            > 0;
+        if app.lives() < STARTING_LIVES {
+            break;
+        }
+    }
+    assert!(fired, "turret didn't shoot");
+    assert_eq!(app.lives(), STARTING_LIVES - 1);
+}
+
+#[test]
+fn turret_holds_fire_while_the_player_is_far() {
+    let mut app = TestApp::new(
+        "⬜⬜⬜⬜⬜⬜⬜⬜⬜⬜⬜⬜\n\
+         🙂⬜⬜⬜⬜⬜⬜⬜⬜⬜⬜🔫\n\
+         🟩🟩🟩🟩🟩🟩🟩🟩🟩🟩🟩🟩\n",
+    );
+
+    for _ in 0..300 {
+        app.step(1);
+        let projectiles = app
+            .world_mut()
+            .query::<&Projectile>()
+            .iter(app.world())
+            .count();
+        assert_eq!(projectiles, 0, "turret shot at a player out of range");
+    }
+}
+
+#[test]
+fn turret_projectiles_fly_through_one_way_platforms() {
+    let mut app = TestApp::new(
+        "⬜⬜⬜⬜⬜⬜\n\
+         🙂⬜🪵⬜⬜🔫\n\
+         🟩🟩🟩🟩🟩🟩\n",
+    );
+
+    for _ in 0..300 {
+        app.step(1);
+        if app.lives() < STARTING_LIVES {
+            break;
+        }
+    }
+    assert_eq!(app.lives(), STARTING_LIVES - 1);
+}
+
+#[test]
+fn patroller_walks_on_one_way_platforms() {
+    let mut app = TestApp::new(
+        "⬜⬜⬜⬜⬜⬜\n\
+         ⬜⬜👾⬜⬜⬜\n\
+         ⬜🪵🪵🪵🪵⬜\n\
+         🙂⬜⬜⬜⬜⬜\n\
+         🟩⬜⬜⬜⬜⬜\n",
+    );
+
+    for _ in 0..300 {
+        app.step(1);
+        let aabb = enemy_aabb_of(&mut app);
+        assert!((aabb.min.y - row_top(2)).abs() < 0.01, "left the logs");
+    }
+}
@@ -0,0 +1,88 @@
+use super::*;
+use crate::campaign::STARTING_LIVES;
+
+fn walk_into(level: &str) -> TestApp {
+    let mut app = TestApp::new(level);
+    app.press(KeyCode::KeyD);
+    for _ in 0..120 {
+        app.step(1);
+        if app.lives() < STARTING_LIVES {
+            break;
+        }
+    }
+    app
+}
+
+#[test]
+fn spikes_cost_a_life() {
+    let app = walk_into(
+        "⬜⬜⬜⬜\n\
+         🙂⬜🔺⬜\n\
+         🟩🟩🟩🟩\n",
+    );
+
+    assert_eq!(app.lives(), STARTING_LIVES - 1);
+}
+
+#[test]
+fn lava_kills_whatever_the_health() {
+    let app = walk_into(
+        "health = 3\n\
+         ⬜⬜⬜⬜\n\
+         🙂⬜⬜⬜\n\
+         🟩🟩🟥🟩\n",
+    );
+
+    assert_eq!(app.lives(), STARTING_LIVES - 1);
+}
+
+#[test]
+fn saw_takes_a_hit_point() {
+    let mut app = TestApp::new(
+        "health = 3\n\
+         ⬜⬜⬜⬜\n\
+         🙂⬜🪚⬜\n\
+         🟩🟩🟩🟩\n",
+    );
+
+    app.press(KeyCode::KeyD);
+    app.step(60);
+
+    let health = app
+        .world_mut()
+        .query::<&Health>()
+        .single(app.world())
+        .current;
+    assert!(health < 3);
+    assert_eq!(app.lives(), STARTING_LIVES);
+}
+
+#[test]
+fn saw_shreds_the_player_in_place() {
+    let mut app = walk_into(
+        "⬜⬜⬜⬜\n\
+         🙂⬜🪚⬜\n\
+         🟩🟩🟩🟩\n",
+    );
+    app.release(KeyCode::KeyD);
+    app.step(1);
+    let hit = app.player_position();
+
+    app.step(32);
+    let scale = app
+        .world_mut()
+        .query_filtered::<&Transform, With<Player>>()
+        .single(app.world())
+        .scale;
+    assert!(scale.x < SCALE * 0.75, "scale {scale}");
+    assert!(app.player_position().distance(hit) < 1.0);
+
+    // Back to full size once respawned
+    app.step(40);
+    let scale = app
+        .world_mut()
+        .query_filtered::<&Transform, With<Player>>()
+        .single(app.world())
+        .scale;
+    assert_eq!(scale, Vec3::splat(SCALE));
+}
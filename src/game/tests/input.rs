@@ -0,0 +1,107 @@
+use super::*;
+use crate::input::{Action, Bindings};
+
+#[test]
+fn arrow_keys_move_the_player() {
+    let mut app = TestApp::new(
+        "⬜⬜🙂⬜⬜\n\
+         🟩🟩🟩🟩🟩\n",
+    );
+    let start = app.player_position();
+
+    app.press(KeyCode::ArrowLeft);
+    app.step(10);
+    app.release(KeyCode::ArrowLeft);
+    assert!(app.player_position().x < start.x, "player didn't move left");
+
+    let left = app.player_position();
+    app.press(KeyCode::ArrowRight);
+    app.step(20);
+    assert!(app.player_position().x > left.x, "player didn't move right");
+}
+
+#[test]
+fn rebound_jump_key() {
+    let mut app = TestApp::new(
+        "⬜⬜⬜\n\
+         ⬜🙂⬜\n\
+         🟩🟩🟩\n",
+    );
+    app.world_mut()
+        .resource_mut::<Bindings>()
+        .set(Action::Jump, vec![KeyCode::KeyW]);
+    let start = app.player_position();
+
+    app.press(KeyCode::Space);
+    app.step(5);
+    app.release(KeyCode::Space);
+    assert_eq!(app.player_position(), start, "old binding still jumps");
+
+    app.press(KeyCode::KeyW);
+    app.step(5);
+    assert!(
+        app.player_position().y > start.y,
+        "new binding doesn't jump"
+    );
+}
+
+#[test]
+fn gamepad_stick_and_button_move_the_player() {
+    let mut app = TestApp::new(
+        "⬜⬜⬜⬜⬜\n\
+         ⬜⬜🙂⬜⬜\n\
+         🟩🟩🟩🟩🟩\n",
+    );
+    let gamepad = app.connect_gamepad();
+    app.step(1);
+    let start = app.player_position();
+
+    app.set_gamepad_axis(gamepad, GamepadAxis::LeftStickX, -1.0);
+    app.step(10);
+    app.set_gamepad_axis(gamepad, GamepadAxis::LeftStickX, 0.0);
+    assert!(app.player_position().x < start.x, "stick didn't move left");
+
+    let left = app.player_position();
+    app.set_gamepad_button(gamepad, GamepadButton::DPadRight, 1.0);
+    app.step(20);
+    app.set_gamepad_button(gamepad, GamepadButton::DPadRight, 0.0);
+    assert!(app.player_position().x > left.x, "d-pad didn't move right");
+
+    let ground = app.player_position().y;
+    app.set_gamepad_button(gamepad, GamepadButton::South, 1.0);
+    app.step(5);
+    assert!(app.player_position().y > ground, "south button didn't jump");
+}
+
+#[test]
+fn unplugged_gamepad_stops_controlling_the_player() {
+    let mut app = TestApp::new(
+        "⬜⬜⬜⬜⬜\n\
+         ⬜⬜🙂⬜⬜\n\
+         🟩🟩🟩🟩🟩\n",
+    );
+    let gamepad = app.connect_gamepad();
+    app.step(1);
+    app.set_gamepad_button(gamepad, GamepadButton::DPadLeft, 1.0);
+    app.step(1);
+    assert!(app
+        .world_mut()
+        .resource::<ButtonInput<Action>>()
+        .pressed(Action::MoveLeft));
+
+    app.disconnect_gamepad(gamepad);
+    app.step(1);
+    assert!(!app
+        .world_mut()
+        .resource::<ButtonInput<Action>>()
+        .pressed(Action::MoveLeft));
+
+    let gamepad = app.connect_gamepad();
+    app.step(1);
+    app.set_gamepad_button(gamepad, GamepadButton::DPadLeft, 1.0);
+    app.step(1);
+    assert!(app
+        .world_mut()
+        .resource::<ButtonInput<Action>>()
+        .pressed(Action::MoveLeft));
+}
@@ -0,0 +1,54 @@
+use super::*;
+use crate::campaign::{LevelItems, Lives, Score};
+
+#[test]
+fn touching_items_adds_to_the_score() {
+    let mut app = TestApp::new(
+        "🙂🪙🪙💎⬜🪙\n\
+         🟩🟩🟩🟩🟩🟩\n",
+    );
+    let items = app.world().resource::<LevelItems>();
+    assert_eq!((items.coins.total, items.gems.total), (3, 1));
+
+    app.press(KeyCode::KeyD);
+    for _ in 0..60 {
+        app.step(1);
+        if app.player_position().x >= 3.0 * TILE_SIZE {
+            break;
+        }
+    }
+
+    let items = app.world().resource::<LevelItems>();
+    assert_eq!(items.coins.to_string(), "2/3");
+    assert_eq!(items.gems.to_string(), "1/1");
+    assert_eq!(app.world().resource::<Score>().0, 12);
+}
+
+#[test]
+fn retrying_drops_the_points_of_the_failed_attempt() {
+    let mut app = TestApp::with_campaign(&[
+        "⬜🙂🪙🏁\n\
+         🟩🟩🟩🟩\n",
+        "🙂🪙⬜⬜\n\
+         🟩🟩⬜⬜\n",
+    ]);
+
+    app.press(KeyCode::KeyD);
+    app.step(40);
+    assert_eq!(app.state(), GameState::LevelComplete);
+    app.release(KeyCode::KeyD);
+    app.step(100);
+    assert_eq!(app.state(), GameState::Game);
+    assert_eq!(app.world().resource::<Score>().0, 1);
+
+    app.world_mut().resource_mut::<Lives>().0 = 1;
+    app.press(KeyCode::KeyD);
+    app.step(150);
+    assert_eq!(app.state(), GameState::GameOver);
+    assert_eq!(app.world().resource::<Score>().0, 2);
+
+    app.click("Retry");
+    app.step(2);
+    assert_eq!(app.state(), GameState::Game);
+    assert_eq!(app.world().resource::<Score>().0, 1);
+}
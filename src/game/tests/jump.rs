@@ -0,0 +1,111 @@
+use super::*;
+
+const OPEN_FIELD: &str = "⬜⬜⬜⬜⬜\n\
+                          ⬜⬜⬜⬜⬜\n\
+                          ⬜⬜⬜⬜⬜\n\
+                          ⬜⬜🙂⬜⬜\n\
+                          🟩🟩🟩🟩🟩\n";
+
+/// Height reached by a jump with the button held for `ticks`.
+fn jump_height(ticks: u32) -> f32 {
+    let mut app = TestApp::new(OPEN_FIELD);
+    app.step(5);
+    let start = app.player_feet();
+
+    app.press(KeyCode::Space);
+    app.step(ticks);
+    app.release(KeyCode::Space);
+    let mut highest = start;
+    for _ in 0..60 {
+        app.step(1);
+        highest = highest.max(app.player_feet());
+    }
+    highest - start
+}
+
+#[test]
+fn releasing_jump_early_cuts_it_short() {
+    let tap = jump_height(1);
+    let held = jump_height(40);
+
+    assert!(tap > 0.0, "tap didn't jump");
+    assert!(
+        tap < held / 2.0,
+        "tap {tap} is too close to full jump {held}"
+    );
+}
+
+#[test]
+fn jump_pressed_before_landing_is_buffered() {
+    let mut app = TestApp::new(OPEN_FIELD);
+    app.step(5);
+    let start = app.player_feet();
+
+    app.press(KeyCode::Space);
+    app.step(1);
+    app.release(KeyCode::Space);
+    // wait until the player is about to land
+    let mut previous = start;
+    for _ in 0..60 {
+        app.step(1);
+        let feet = app.player_feet();
+        if feet < previous && feet - start < 10.0 {
+            break;
+        }
+        previous = feet;
+    }
+    assert!(
+        app.player_feet() > start,
+        "player landed before the second press"
+    );
+
+    app.press(KeyCode::Space);
+    app.step(1);
+    app.release(KeyCode::Space);
+    let mut highest = start;
+    for _ in 0..20 {
+        app.step(1);
+        highest = highest.max(app.player_feet());
+    }
+    assert!(highest > start + 10.0, "buffered jump was lost");
+}
+
+const LEDGE: &str = "⬜⬜⬜⬜⬜⬜\n\
+                     ⬜🙂⬜⬜⬜⬜\n\
+                     🟩🟩⬜⬜⬜⬜\n\
+                     ⬜⬜⬜⬜⬜⬜\n\
+                     ⬜⬜⬜⬜⬜⬜\n\
+                     🟩🟩🟩🟩🟩🟩\n";
+
+/// Walk off the ledge, and press jump `delay` ticks after leaving the ground. Returns the vertical
+/// movement over the following ticks.
+fn jump_after_ledge(delay: u32) -> f32 {
+    let mut app = TestApp::new(LEDGE);
+    app.step(5);
+    app.press(KeyCode::ArrowRight);
+    for _ in 0..60 {
+        if !app
+            .player_contacts()
+            .iter()
+            .any(|contact| contact.normal == Vec2::Y)
+        {
+            break;
+        }
+        app.step(1);
+    }
+    app.step(delay);
+    let before = app.player_position().y;
+
+    app.press(KeyCode::Space);
+    app.step(5);
+    app.player_position().y - before
+}
+
+#[test]
+fn coyote_time_allows_jumping_after_leaving_a_ledge() {
+    assert!(jump_after_ledge(2) > 0.0, "late jump was ignored");
+    assert!(
+        jump_after_ledge(20) < 0.0,
+        "player jumped long after leaving the ledge"
+    );
+}
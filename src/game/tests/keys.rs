@@ -0,0 +1,33 @@
+use super::*;
+
+#[test]
+fn door_blocks_the_player_without_a_key() {
+    let mut app = TestApp::new(
+        "⬜⬜⬜⬜⬜\n\
+         🙂⬜🟨⬜⬜\n\
+         🟩🟩🟩🟩🟩\n",
+    );
+
+    app.press(KeyCode::KeyD);
+    app.step(60);
+
+    assert!(app.player_position().x < 2.0 * TILE_SIZE);
+}
+
+#[test]
+fn key_opens_the_door_of_its_colour() {
+    let mut app = TestApp::new(
+        "⬜⬜⬜⬜⬜⬜⬜\n\
+         🙂⬜🟡⬜🟨⬜🟦\n\
+         🟩🟩🟩🟩🟩🟩🟩\n",
+    );
+
+    app.press(KeyCode::KeyD);
+    app.step(120);
+
+    // Through the yellow door, which used the key, stopped by the blue one
+    let x = app.player_position().x;
+    assert!(x > 4.0 * TILE_SIZE && x < 6.0 * TILE_SIZE, "player at {x}");
+    let inventory = app.world_mut().query::<&Inventory>().single(app.world());
+    assert!(inventory.keys.is_empty());
+}
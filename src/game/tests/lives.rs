@@ -0,0 +1,105 @@
+use super::*;
+use crate::campaign::STARTING_LIVES;
+
+const FALL: &str = "⬜🙂⬜⬜\n\
+                    ⬜🟩⬜⬜\n\
+                    ⬜⬜⬜⬜\n";
+
+fn player_health(app: &mut TestApp) -> Option<u32> {
+    app.world_mut()
+        .query_filtered::<&Health, With<Player>>()
+        .get_single(app.world())
+        .ok()
+        .map(|health| health.current)
+}
+
+#[test]
+fn falling_off_the_level_respawns_the_player() {
+    let mut app = TestApp::new(FALL);
+    let spawn = app.player_position();
+
+    app.press(KeyCode::KeyD);
+    for _ in 0..60 {
+        app.step(1);
+        if app.lives() < STARTING_LIVES {
+            break;
+        }
+    }
+    assert_eq!(app.lives(), STARTING_LIVES - 1);
+    app.release(KeyCode::KeyD);
+
+    // Death animation
+    app.step(70);
+
+    assert_eq!(app.state(), GameState::Game);
+    assert_eq!(app.player_position(), spawn);
+}
+
+#[test]
+fn losing_the_last_life_ends_the_game() {
+    let mut app = TestApp::new(FALL);
+    app.world_mut().resource_mut::<Lives>().0 = 1;
+
+    app.press(KeyCode::KeyD);
+    app.step(150);
+
+    assert_eq!(app.lives(), 0);
+    assert_eq!(app.state(), GameState::GameOver);
+}
+
+#[test]
+fn health_absorbs_hits_before_losing_a_life() {
+    let mut app = TestApp::new(
+        "health = 2\n\
+         ⬜⬜⬜⬜⬜⬜\n\
+         🙂⬜⬜⬜⬜🔫\n\
+         🟩🟩🟩🟩🟩🟩\n",
+    );
+    assert_eq!(player_health(&mut app), Some(2));
+
+    for _ in 0..300 {
+        app.step(1);
+        if player_health(&mut app) != Some(2) {
+            break;
+        }
+    }
+    assert_eq!(player_health(&mut app), Some(1));
+    assert_eq!(app.lives(), STARTING_LIVES);
+
+    for _ in 0..600 {
+        app.step(1);
+        if app.lives() < STARTING_LIVES {
+            break;
+        }
+    }
+    assert_eq!(app.lives(), STARTING_LIVES - 1);
+}
+
+#[test]
+fn checkpoint_becomes_the_respawn_point() {
+    let mut app = TestApp::new(
+        "⬜🙂⬜🚩⬜⬜\n\
+         🟩🟩🟩🟩⬜⬜\n\
+         ⬜⬜⬜⬜⬜⬜\n",
+    );
+
+    app.press(KeyCode::KeyD);
+    for _ in 0..120 {
+        app.step(1);
+        if app.lives() < STARTING_LIVES {
+            break;
+        }
+    }
+    app.release(KeyCode::KeyD);
+    assert!(
+        app.world_mut()
+            .query::<&Checkpoint>()
+            .single(app.world())
+            .active
+    );
+
+    app.step(70);
+
+    assert_eq!(app.lives(), STARTING_LIVES - 1);
+    assert_eq!(app.player_position().x, 3.0 * TILE_SIZE);
+}
@@ -0,0 +1,247 @@
+use bevy::{
+    input::{
+        gamepad::{
+            GamepadConnection, GamepadConnectionEvent, RawGamepadAxisChangedEvent,
+            RawGamepadButtonChangedEvent, RawGamepadEvent,
+        },
+        keyboard::{Key, KeyboardInput},
+        ButtonState, InputPlugin,
+    },
+    state::app::StatesPlugin,
+    time::TimeUpdateStrategy,
+};
+
+use super::*;
+use crate::{
+    campaign::Lives, input::BindingsPath, level_loader::CampaignLevel, menu::ButtonActivated,
+    AudioAssets,
+};
+
+mod abilities;
+mod bench;
+mod collisions;
+mod enemies;
+mod hazards;
+mod input;
+mod items;
+mod jump;
+mod keys;
+mod lives;
+mod platforms;
+mod progress;
+
+/// The game running on an inline campaign through [`crate::app_plugin`], without rendering,
+/// windowing or audio output.
+///
+/// Each [`TestApp::step`] runs exactly one `FixedUpdate` tick.
+struct TestApp {
+    app: App,
+}
+
+impl TestApp {
+    fn new(level: &str) -> Self {
+        Self::with_campaign(&[level])
+    }
+
+    fn with_campaign(levels: &[&str]) -> Self {
+        let mut app = App::new();
+        app.add_plugins((
+            MinimalPlugins,
+            AssetPlugin::default(),
+            InputPlugin,
+            StatesPlugin,
+        ))
+        .add_plugins(crate::app_plugin)
+        .init_asset::<Mesh>()
+        .init_asset::<FlagMaterial>()
+        // Never written, the tests start from the default bindings
+        .insert_resource(BindingsPath(
+            std::env::temp_dir().join("bevy_workshop_test_harness.cfg"),
+        ))
+        .insert_resource(TimeUpdateStrategy::ManualDuration(
+            Time::<Fixed>::default().timestep(),
+        ))
+        .insert_resource(GameAssets {
+            player_image: default(),
+            player_layout: default(),
+            ground_image: default(),
+            ground_layout: default(),
+            items_image: default(),
+            items_layout: default(),
+            enemy_image: default(),
+            enemy_layout: default(),
+            tiles_image: default(),
+            tiles_layout: default(),
+            hud_image: default(),
+            hud_layout: default(),
+        })
+        .insert_resource(AudioAssets {
+            jump: default(),
+            double_jump: default(),
+            lost: default(),
+            checkpoint: default(),
+            pickup: default(),
+            spikes: default(),
+            lava: default(),
+            saw: default(),
+        });
+
+        let levels = levels
+            .iter()
+            .enumerate()
+            .map(|(index, level)| CampaignLevel {
+                name: format!("Level {index}"),
+                level: app
+                    .world_mut()
+                    .resource_mut::<Assets<Level>>()
+                    .add(level.parse::<Level>().unwrap()),
+            })
+            .collect();
+        let campaign = app
+            .world_mut()
+            .resource_mut::<Assets<Campaign>>()
+            .add(Campaign { levels });
+        app.insert_resource(LoadedCampaign { campaign });
+
+        app.world_mut()
+            .resource_mut::<NextState<GameState>>()
+            .set(GameState::Game);
+        app.update();
+        Self { app }
+    }
+
+    fn world(&self) -> &World {
+        self.app.world()
+    }
+
+    fn world_mut(&mut self) -> &mut World {
+        self.app.world_mut()
+    }
+
+    fn step(&mut self, ticks: u32) {
+        for _ in 0..ticks {
+            self.app.update();
+        }
+    }
+
+    fn send_key(&mut self, key_code: KeyCode, state: ButtonState) {
+        self.app.world_mut().send_event(KeyboardInput {
+            key_code,
+            logical_key: Key::Unidentified(bevy::input::keyboard::NativeKey::Unidentified),
+            state,
+            repeat: false,
+            window: Entity::PLACEHOLDER,
+        });
+    }
+
+    fn press(&mut self, key_code: KeyCode) {
+        self.send_key(key_code, ButtonState::Pressed);
+    }
+
+    fn release(&mut self, key_code: KeyCode) {
+        self.send_key(key_code, ButtonState::Released);
+    }
+
+    fn connect_gamepad(&mut self) -> Entity {
+        let gamepad = self.app.world_mut().spawn_empty().id();
+        self.app.world_mut().send_event(GamepadConnectionEvent::new(
+            gamepad,
+            GamepadConnection::Connected {
+                name: "Test Gamepad".to_string(),
+                vendor_id: None,
+                product_id: None,
+            },
+        ));
+        gamepad
+    }
+
+    fn disconnect_gamepad(&mut self, gamepad: Entity) {
+        self.app.world_mut().send_event(GamepadConnectionEvent::new(
+            gamepad,
+            GamepadConnection::Disconnected,
+        ));
+    }
+
+    fn set_gamepad_button(&mut self, gamepad: Entity, button: GamepadButton, value: f32) {
+        self.app.world_mut().send_event(RawGamepadEvent::Button(
+            RawGamepadButtonChangedEvent::new(gamepad, button, value),
+        ));
+    }
+
+    fn set_gamepad_axis(&mut self, gamepad: Entity, axis: GamepadAxis, value: f32) {
+        self.app
+            .world_mut()
+            .send_event(RawGamepadEvent::Axis(RawGamepadAxisChangedEvent::new(
+                gamepad, axis, value,
+            )));
+    }
+
+    /// Activate the button labelled `label` on the current screen.
+    fn click(&mut self, label: &str) {
+        let button = self
+            .app
+            .world_mut()
+            .query_filtered::<(Entity, &Children), With<Button>>()
+            .iter(self.app.world())
+            .find(|(_, children)| {
+                children.iter().any(|child| {
+                    self.app
+                        .world()
+                        .get::<Text>(*child)
+                        .is_some_and(|text| text.0 == label)
+                })
+            })
+            .map(|(entity, _)| entity)
+            .unwrap();
+        self.app.world_mut().send_event(ButtonActivated(button));
+    }
+
+    fn state(&self) -> GameState {
+        *self.app.world().resource::<State<GameState>>().get()
+    }
+
+    fn lives(&self) -> u32 {
+        self.app.world().resource::<Lives>().0
+    }
+
+    fn player_position(&mut self) -> Vec2 {
+        self.app
+            .world_mut()
+            .query_filtered::<&Transform, With<Player>>()
+            .single(self.app.world())
+            .translation
+            .xy()
+    }
+
+    /// Bottom edge of the player collision box.
+    fn player_feet(&mut self) -> f32 {
+        self.player_position().y - 256.0 / 2.0 * SCALE
+    }
+
+    fn player_velocity(&mut self) -> Vec2 {
+        self.app
+            .world_mut()
+            .query_filtered::<&Velocity, With<Player>>()
+            .single(self.app.world())
+            .current
+    }
+
+    fn player_contacts(&mut self) -> Vec<Contact> {
+        self.app
+            .world_mut()
+            .query_filtered::<&Contacts, With<Player>>()
+            .single(self.app.world())
+            .hits
+            .clone()
+    }
+}
+
+/// Top edge of the tiles at row `j`.
+fn row_top(j: usize) -> f32 {
+    -(j as f32) * TILE_SIZE + TILE_SIZE / 2.0
+}
+
+/// Left edge of the tiles at column `i`.
+fn column_left(i: usize) -> f32 {
+    i as f32 * TILE_SIZE - TILE_SIZE / 2.0
+}
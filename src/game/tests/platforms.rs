@@ -0,0 +1,193 @@
+use bevy::math::bounding::{Aabb2d, IntersectsVolume};
+
+use super::*;
+use crate::{
+    campaign::STARTING_LIVES,
+    game::{enemy::Enemy, platform::MovingPlatform, player::player_aabb},
+};
+
+fn on_ground(app: &mut TestApp) -> bool {
+    app.player_contacts()
+        .iter()
+        .any(|contact| contact.normal == Vec2::Y)
+}
+
+#[test]
+fn player_rides_a_horizontal_platform() {
+    let mut app = TestApp::new(
+        "platform = horizontal 3 2\n\
+         ⬜⬜⬜⬜⬜⬜\n\
+         🙂⬜⬜⬜⬜⬜\n\
+         🟫🟫⬜⬜⬜⬜\n",
+    );
+    let start = app.player_position();
+
+    // Half a round trip, the platform is at the far end
+    app.step(96);
+
+    assert!(on_ground(&mut app));
+    let moved = app.player_position().x - start.x;
+    assert!(
+        (moved - 3.0 * TILE_SIZE).abs() < TILE_SIZE / 4.0,
+        "moved {moved}"
+    );
+}
+
+#[test]
+fn player_rides_a_vertical_platform() {
+    let mut app = TestApp::new(
+        "platform = vertical 2 2\n\
+         ⬜⬜⬜\n\
+         ⬜⬜⬜\n\
+         ⬜⬜⬜\n\
+         ⬜🙂⬜\n\
+         ⬜🟫⬜\n",
+    );
+    let start = app.player_position();
+
+    // Up and back down, the player stays on it all along
+    for tick in 0..128 {
+        app.step(1);
+        if tick > 8 {
+            assert!(on_ground(&mut app), "fell off at tick {tick}");
+        }
+        if tick == 64 {
+            let moved = app.player_position().y - start.y;
+            assert!(
+                (moved - 2.0 * TILE_SIZE).abs() < TILE_SIZE / 4.0,
+                "moved {moved}"
+            );
+        }
+    }
+}
+
+fn platform_aabb(app: &mut TestApp) -> Aabb2d {
+    let (transform, platform) = app
+        .world_mut()
+        .query::<(&Transform, &MovingPlatform)>()
+        .single(app.world());
+    platform.aabb(transform)
+}
+
+#[test]
+fn horizontal_platform_pushes_the_player_out_of_its_way() {
+    let mut app = TestApp::new(
+        "platform = horizontal 3 2\n\
+         ⬜⬜⬜⬜⬜⬜\n\
+         🟫⬜⬜🙂⬜⬜\n\
+         🟩🟩🟩🟩🟩🟩\n",
+    );
+    let start = app.player_position();
+
+    for tick in 0..96 {
+        app.step(1);
+        let player = player_aabb(
+            app.world_mut()
+                .query_filtered::<&Transform, With<Player>>()
+                .single(app.world()),
+        );
+        let mut platform = platform_aabb(&mut app);
+        platform.min.x += 1.0;
+        platform.max.x -= 1.0;
+        assert!(!platform.intersects(&player), "overlapped at tick {tick}");
+    }
+    assert!(app.player_position().x > start.x + TILE_SIZE / 4.0);
+}
+
+#[test]
+fn platform_pushing_the_player_into_a_wall_crushes_them() {
+    let mut app = TestApp::new(
+        "platform = horizontal 3 2\n\
+         ⬜⬜⬜⬜⬜⬜\n\
+         🟫⬜⬜🙂🟩⬜\n\
+         🟩🟩🟩🟩🟩🟩\n",
+    );
+    let mut wall = Aabb2d::new(
+        Vec2::new(
+            column_left(4) + TILE_SIZE / 2.0,
+            row_top(1) - TILE_SIZE / 2.0,
+        ),
+        Vec2::splat(TILE_SIZE / 2.0),
+    );
+    wall.min.x += 1.0;
+
+    for tick in 0..96 {
+        app.step(1);
+        let player = player_aabb(
+            app.world_mut()
+                .query_filtered::<&Transform, With<Player>>()
+                .single(app.world()),
+        );
+        assert!(
+            !wall.intersects(&player),
+            "pushed into the wall at tick {tick}"
+        );
+    }
+    assert_eq!(app.lives(), STARTING_LIVES - 1);
+}
+
+#[test]
+fn patroller_stays_on_a_moving_platform() {
+    let mut app = TestApp::new(
+        "platform = horizontal 2 1\n\
+         ⬜⬜⬜⬜⬜⬜\n\
+         ⬜👾⬜⬜⬜⬜\n\
+         🟫🟫🟫⬜⬜⬜\n\
+         ⬜⬜⬜⬜⬜⬜\n\
+         🙂⬜⬜⬜⬜⬜\n\
+         🟩⬜⬜⬜⬜⬜\n",
+    );
+
+    for tick in 0..300 {
+        app.step(1);
+        let enemy = app
+            .world_mut()
+            .query::<(&Enemy, &Transform)>()
+            .get_single(app.world())
+            .map(|(enemy, transform)| enemy.aabb(transform));
+        let Ok(enemy) = enemy else {
+            panic!("fell off the level at tick {tick}");
+        };
+        let platform = platform_aabb(&mut app);
+        assert!(
+            (enemy.min.y - platform.max.y).abs() < 1.0,
+            "left the platform at tick {tick}"
+        );
+        assert!(enemy.max.x > platform.min.x && enemy.min.x < platform.max.x);
+    }
+}
+
+const ONE_WAY: &str = "⬜⬜⬜\n\
+                       ⬜⬜⬜\n\
+                       ⬜🪵⬜\n\
+                       ⬜⬜⬜\n\
+                       ⬜🙂⬜\n\
+                       ⬜🟩⬜\n";
+
+#[test]
+fn jump_through_a_one_way_platform_and_land_on_it() {
+    let mut app = TestApp::new(ONE_WAY);
+
+    app.press(KeyCode::Space);
+    app.step(90);
+
+    assert!(on_ground(&mut app));
+    assert!((app.player_feet() - row_top(2)).abs() < 1.0);
+}
+
+#[test]
+fn pressing_down_drops_through_a_one_way_platform() {
+    let mut app = TestApp::new(ONE_WAY);
+    app.press(KeyCode::Space);
+    app.step(90);
+    app.release(KeyCode::Space);
+    app.step(1);
+
+    app.press(KeyCode::KeyS);
+    app.step(5);
+    app.release(KeyCode::KeyS);
+    app.step(60);
+
+    assert!(on_ground(&mut app));
+    assert!((app.player_feet() - row_top(5)).abs() < 1.0);
+}
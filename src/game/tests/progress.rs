@@ -0,0 +1,36 @@
+use super::*;
+use crate::campaign::CurrentLevel;
+
+#[test]
+fn reaching_the_flag_completes_the_campaign() {
+    let mut app = TestApp::new(
+        "⬜🙂⬜⬜🏁⬜\n\
+         🟩🟩🟩🟩🟩🟩\n",
+    );
+
+    app.press(KeyCode::KeyD);
+    app.step(60);
+
+    assert_eq!(app.state(), GameState::CampaignComplete);
+}
+
+#[test]
+fn reaching_the_flag_starts_the_next_level() {
+    let mut app = TestApp::with_campaign(&[
+        "⬜🙂⬜🏁\n\
+         🟩🟩🟩🟩\n",
+        "🙂⬜⬜⬜\n\
+         🟩🟩🟩🟩\n",
+    ]);
+
+    app.press(KeyCode::KeyD);
+    app.step(40);
+    assert_eq!(app.state(), GameState::LevelComplete);
+    assert_eq!(app.world_mut().resource::<CurrentLevel>().0, 1);
+
+    app.release(KeyCode::KeyD);
+    app.step(100);
+    assert_eq!(app.state(), GameState::Game);
+    // spawned on the first tile of the second level
+    assert_eq!(app.player_position().x, 0.0);
+}
@@ -0,0 +1,387 @@
+use std::path::{Path, PathBuf};
+
+use bevy::{
+    input::{
+        gamepad::{GamepadConnection, GamepadConnectionEvent},
+        InputSystem,
+    },
+    prelude::*,
+    reflect::{DynamicEnum, DynamicVariant, TypeInfo, Typed, VariantInfo},
+    utils::HashMap,
+};
+
+pub fn input_plugin(app: &mut App) {
+    app.init_resource::<Bindings>()
+        .init_resource::<BindingsPath>()
+        .init_resource::<ButtonInput<Action>>()
+        .init_resource::<ButtonInput<MenuAction>>()
+        .add_systems(
+            PreUpdate,
+            (log_gamepad_connections, update_actions, update_menu_actions)
+                .in_set(ActionSystem)
+                .after(InputSystem),
+        );
+}
+
+/// Systems updating `ButtonInput<Action>` and `ButtonInput<MenuAction>`.
+#[derive(SystemSet, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ActionSystem;
+
+/// How far the left stick must be pushed to move the player.
+const STICK_THRESHOLD: f32 = 0.5;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Action {
+    MoveLeft,
+    MoveRight,
+    /// Drop through one-way platforms.
+    MoveDown,
+    Jump,
+    Dash,
+    Pause,
+}
+
+impl Action {
+    pub const ALL: [Action; 6] = [
+        Action::MoveLeft,
+        Action::MoveRight,
+        Action::MoveDown,
+        Action::Jump,
+        Action::Dash,
+        Action::Pause,
+    ];
+
+    /// Name of the action in the bindings file.
+    fn key(self) -> &'static str {
+        match self {
+            Action::MoveLeft => "move_left",
+            Action::MoveRight => "move_right",
+            Action::MoveDown => "move_down",
+            Action::Jump => "jump",
+            Action::Dash => "dash",
+            Action::Pause => "pause",
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Action::MoveLeft => "Move Left",
+            Action::MoveRight => "Move Right",
+            Action::MoveDown => "Move Down",
+            Action::Jump => "Jump",
+            Action::Dash => "Dash",
+            Action::Pause => "Pause",
+        }
+    }
+
+    /// Gamepad controls are not rebindable: d-pad or left stick to move, south button to jump,
+    /// west button or right trigger to dash.
+    fn gamepad_pressed(self, gamepad: &Gamepad) -> bool {
+        match self {
+            Action::MoveLeft => {
+                gamepad.pressed(GamepadButton::DPadLeft)
+                    || gamepad.left_stick().x < -STICK_THRESHOLD
+            }
+            Action::MoveRight => {
+                gamepad.pressed(GamepadButton::DPadRight)
+                    || gamepad.left_stick().x > STICK_THRESHOLD
+            }
+            Action::MoveDown => {
+                gamepad.pressed(GamepadButton::DPadDown)
+                    || gamepad.left_stick().y < -STICK_THRESHOLD
+            }
+            Action::Jump => gamepad.pressed(GamepadButton::South),
+            Action::Dash => {
+                gamepad.pressed(GamepadButton::West) || gamepad.pressed(GamepadButton::RightTrigger)
+            }
+            Action::Pause => gamepad.pressed(GamepadButton::Start),
+        }
+    }
+}
+
+/// Navigation between the buttons of a screen. Unlike [`Action`], its keys are not rebindable.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum MenuAction {
+    Up,
+    Down,
+    Left,
+    Right,
+    Confirm,
+    Back,
+}
+
+impl MenuAction {
+    pub const ALL: [MenuAction; 6] = [
+        MenuAction::Up,
+        MenuAction::Down,
+        MenuAction::Left,
+        MenuAction::Right,
+        MenuAction::Confirm,
+        MenuAction::Back,
+    ];
+
+    fn keys(self) -> &'static [KeyCode] {
+        match self {
+            MenuAction::Up => &[KeyCode::ArrowUp, KeyCode::KeyW],
+            MenuAction::Down => &[KeyCode::ArrowDown, KeyCode::KeyS],
+            MenuAction::Left => &[KeyCode::ArrowLeft, KeyCode::KeyA],
+            MenuAction::Right => &[KeyCode::ArrowRight, KeyCode::KeyD],
+            MenuAction::Confirm => &[KeyCode::Enter, KeyCode::NumpadEnter, KeyCode::Space],
+            MenuAction::Back => &[KeyCode::Escape],
+        }
+    }
+
+    fn gamepad_button(self) -> GamepadButton {
+        match self {
+            MenuAction::Up => GamepadButton::DPadUp,
+            MenuAction::Down => GamepadButton::DPadDown,
+            MenuAction::Left => GamepadButton::DPadLeft,
+            MenuAction::Right => GamepadButton::DPadRight,
+            MenuAction::Confirm => GamepadButton::South,
+            MenuAction::Back => GamepadButton::East,
+        }
+    }
+}
+
+/// Keys triggering each [`Action`].
+#[derive(Resource, Debug, Clone, PartialEq)]
+pub struct Bindings {
+    keys: HashMap<Action, Vec<KeyCode>>,
+}
+
+impl Default for Bindings {
+    fn default() -> Self {
+        Self {
+            keys: HashMap::from([
+                (Action::MoveLeft, vec![KeyCode::KeyA, KeyCode::ArrowLeft]),
+                (Action::MoveRight, vec![KeyCode::KeyD, KeyCode::ArrowRight]),
+                (Action::MoveDown, vec![KeyCode::KeyS, KeyCode::ArrowDown]),
+                (Action::Jump, vec![KeyCode::Space, KeyCode::ArrowUp]),
+                (Action::Dash, vec![KeyCode::ShiftLeft, KeyCode::KeyK]),
+                (Action::Pause, vec![KeyCode::Escape, KeyCode::KeyP]),
+            ]),
+        }
+    }
+}
+
+/// File the bindings are loaded from and saved to.
+#[derive(Resource, Debug, Clone)]
+pub struct BindingsPath(pub PathBuf);
+
+impl Default for BindingsPath {
+    fn default() -> Self {
+        Self(PathBuf::from("bindings.cfg"))
+    }
+}
+
+impl Bindings {
+    pub fn keys(&self, action: Action) -> &[KeyCode] {
+        self.keys
+            .get(&action)
+            .map(Vec::as_slice)
+            .unwrap_or_default()
+    }
+
+    pub fn set(&mut self, action: Action, keys: Vec<KeyCode>) {
+        self.keys.insert(action, keys);
+    }
+
+    /// Make `key` the main key of `action`, keeping its other keys. An action already triggered
+    /// by `key` gets the previous main key of `action` instead, so that no key does two things.
+    pub fn rebind(&mut self, action: Action, key: KeyCode) {
+        let previous = self.keys(action).first().copied();
+        if previous == Some(key) {
+            return;
+        }
+        for (other, keys) in &mut self.keys {
+            if *other == action {
+                continue;
+            }
+            match previous {
+                Some(previous) => keys
+                    .iter_mut()
+                    .filter(|bound| **bound == key)
+                    .for_each(|bound| *bound = previous),
+                None => keys.retain(|bound| *bound != key),
+            }
+        }
+        let keys = self.keys.entry(action).or_default();
+        if let Some(index) = keys.iter().position(|bound| *bound == key) {
+            keys.swap(0, index);
+        } else if keys.is_empty() {
+            keys.push(key);
+        } else {
+            keys[0] = key;
+        }
+    }
+
+    /// Parse a bindings file, with one `action = Key, Key` line per action. Actions missing from
+    /// the file and unknown keys keep their default binding.
+    pub fn from_config(config: &str) -> Self {
+        let mut bindings = Self::default();
+        for line in config.lines() {
+            let Some((name, keys)) = line.split_once('=') else {
+                continue;
+            };
+            let Some(action) = Action::ALL
+                .into_iter()
+                .find(|action| action.key() == name.trim())
+            else {
+                warn!("Unknown action in bindings: {}", name.trim());
+                continue;
+            };
+            let keys = keys
+                .split(',')
+                .map(str::trim)
+                .filter(|key| !key.is_empty())
+                .map(|key| parse_key(key).ok_or(key))
+                .collect::<Result<Vec<_>, _>>();
+            match keys {
+                Ok(keys) => bindings.set(action, keys),
+                Err(key) => warn!("Unknown key in bindings: {key}"),
+            }
+        }
+        bindings
+    }
+
+    pub fn to_config(&self) -> String {
+        Action::ALL
+            .into_iter()
+            .map(|action| {
+                let keys = self
+                    .keys(action)
+                    .iter()
+                    .map(|key| format!("{key:?}"))
+                    .collect::<Vec<_>>();
+                format!("{} = {}\n", action.key(), keys.join(", "))
+            })
+            .collect()
+    }
+
+    /// Read the bindings file, falling back to the default bindings if there is none.
+    pub fn load(path: &Path) -> Self {
+        #[cfg(not(target_arch = "wasm32"))]
+        if let Ok(config) = std::fs::read_to_string(path) {
+            return Self::from_config(&config);
+        }
+        #[cfg(target_arch = "wasm32")]
+        let _ = path;
+        Self::default()
+    }
+
+    pub fn save(&self, path: &Path) {
+        #[cfg(not(target_arch = "wasm32"))]
+        if let Err(error) = std::fs::write(path, self.to_config()) {
+            error!("Could not save bindings: {error}");
+        }
+        #[cfg(target_arch = "wasm32")]
+        let _ = path;
+    }
+}
+
+fn parse_key(name: &str) -> Option<KeyCode> {
+    let TypeInfo::Enum(info) = KeyCode::type_info() else {
+        return None;
+    };
+    if !matches!(info.variant(name)?, VariantInfo::Unit(_)) {
+        return None;
+    }
+    KeyCode::from_reflect(&DynamicEnum::new(name, DynamicVariant::Unit))
+}
+
+fn log_gamepad_connections(mut connections: EventReader<GamepadConnectionEvent>) {
+    for connection in connections.read() {
+        match &connection.connection {
+            GamepadConnection::Connected { name, .. } => info!("Gamepad connected: {name}"),
+            GamepadConnection::Disconnected => info!("Gamepad disconnected"),
+        }
+    }
+}
+
+fn update_actions(
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    gamepads: Query<&Gamepad>,
+    bindings: Res<Bindings>,
+    mut actions: ResMut<ButtonInput<Action>>,
+) {
+    actions.clear();
+    for action in Action::ALL {
+        if keyboard_input.any_pressed(bindings.keys(action).iter().copied())
+            || gamepads
+                .iter()
+                .any(|gamepad| action.gamepad_pressed(gamepad))
+        {
+            actions.press(action);
+        } else {
+            actions.release(action);
+        }
+    }
+}
+
+fn update_menu_actions(
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    gamepads: Query<&Gamepad>,
+    mut actions: ResMut<ButtonInput<MenuAction>>,
+) {
+    actions.clear();
+    for action in MenuAction::ALL {
+        if keyboard_input.any_pressed(action.keys().iter().copied())
+            || gamepads
+                .iter()
+                .any(|gamepad| gamepad.pressed(action.gamepad_button()))
+        {
+            actions.press(action);
+        } else {
+            actions.release(action);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bindings_config_round_trip() {
+        let mut bindings = Bindings::default();
+        bindings.set(Action::Jump, vec![KeyCode::KeyW, KeyCode::Numpad8]);
+
+        assert_eq!(Bindings::from_config(&bindings.to_config()), bindings);
+    }
+
+    #[test]
+    fn bindings_config_keeps_defaults() {
+        let bindings = Bindings::from_config("jump = KeyW\nmove_left = NotAKey\n");
+
+        assert_eq!(bindings.keys(Action::Jump), [KeyCode::KeyW]);
+        assert_eq!(
+            bindings.keys(Action::MoveLeft),
+            Bindings::default().keys(Action::MoveLeft)
+        );
+    }
+
+    #[test]
+    fn rebinding_a_used_key_swaps_it() {
+        let mut bindings = Bindings::default();
+        bindings.rebind(Action::MoveLeft, KeyCode::Space);
+
+        assert_eq!(
+            bindings.keys(Action::MoveLeft),
+            [KeyCode::Space, KeyCode::ArrowLeft]
+        );
+        assert_eq!(
+            bindings.keys(Action::Jump),
+            [KeyCode::KeyA, KeyCode::ArrowUp]
+        );
+    }
+
+    #[test]
+    fn rebinding_a_secondary_key_makes_it_the_main_one() {
+        let mut bindings = Bindings::default();
+        bindings.rebind(Action::Jump, KeyCode::ArrowUp);
+
+        let mut expected = Bindings::default();
+        expected.set(Action::Jump, vec![KeyCode::ArrowUp, KeyCode::Space]);
+        assert_eq!(bindings, expected);
+    }
+}
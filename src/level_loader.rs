@@ -1,3 +1,5 @@
+use std::str::FromStr;
+
 use bevy::{
     asset::{io::Reader, AssetLoader, AsyncReadExt, LoadContext},
     prelude::*,
@@ -5,17 +7,149 @@ use bevy::{
 use thiserror::Error;
 
 pub fn level_loader_plugin(app: &mut App) {
-    app.init_asset::<Level>().init_asset_loader::<LevelLoader>();
+    app.init_asset::<Level>()
+        .init_asset_loader::<LevelLoader>()
+        .init_asset::<Campaign>()
+        .init_asset_loader::<CampaignLoader>();
 }
 
 #[derive(Resource)]
-pub struct LoadedLevel {
+pub struct LoadedCampaign {
+    pub campaign: Handle<Campaign>,
+}
+
+#[derive(Asset, TypePath, Debug)]
+pub struct Campaign {
+    pub levels: Vec<CampaignLevel>,
+}
+
+#[derive(Debug)]
+pub struct CampaignLevel {
+    pub name: String,
     pub level: Handle<Level>,
 }
 
 #[derive(Asset, TypePath, Debug)]
 pub struct Level {
     pub tiles: Vec<Vec<Tile>>,
+    /// Abilities the player starts the level with.
+    pub abilities: Vec<Ability>,
+    /// Hits the player can take before dying, a single hit kills when unset.
+    pub health: Option<u32>,
+    /// Paths of the moving platforms, in the order their first tile appears.
+    pub platforms: Vec<PlatformPath>,
+}
+
+impl FromStr for Level {
+    type Err = LevelLoaderError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut tiles = vec![];
+        let mut abilities = vec![];
+        let mut health = None;
+        let mut platforms = vec![];
+        let mut line = vec![];
+        for row in s.split_inclusive('\n') {
+            // Metadata lines, as `key = value`
+            if let Some((key, value)) = row.split_once('=') {
+                match key.trim() {
+                    "abilities" => {
+                        for ability in value.split(',').map(str::trim) {
+                            abilities.push(ability.parse()?);
+                        }
+                    }
+                    "health" => {
+                        let value = value.trim();
+                        match value.parse() {
+                            Ok(hits) if hits > 0 => health = Some(hits),
+                            _ => Err(LevelLoaderError::InvalidHealth(value.to_string()))?,
+                        }
+                    }
+                    "platform" => platforms.push(value.parse()?),
+                    key => Err(LevelLoaderError::UnknownMetadata(key.to_string()))?,
+                }
+                continue;
+            }
+            for char in row.chars() {
+                match char {
+                    '⬜' => line.push(Tile::Empty),
+                    '🟩' => line.push(Tile::Ground),
+                    '🙂' => line.push(Tile::Spawn),
+                    '🏁' => line.push(Tile::Flag),
+                    '🚩' => line.push(Tile::Checkpoint),
+                    '🪙' => line.push(Tile::Item(Item::Coin)),
+                    '💎' => line.push(Tile::Item(Item::Gem)),
+                    '🟡' => line.push(Tile::Key(KeyColor::Yellow)),
+                    '🟠' => line.push(Tile::Key(KeyColor::Orange)),
+                    '🔵' => line.push(Tile::Key(KeyColor::Blue)),
+                    '🟨' => line.push(Tile::Door(KeyColor::Yellow)),
+                    '🟧' => line.push(Tile::Door(KeyColor::Orange)),
+                    '🟦' => line.push(Tile::Door(KeyColor::Blue)),
+                    '🟫' => line.push(Tile::Platform),
+                    '🪵' => line.push(Tile::OneWay),
+                    '🔺' => line.push(Tile::Hazard(Hazard::Spikes)),
+                    '🟥' => line.push(Tile::Hazard(Hazard::Lava)),
+                    '🪚' => line.push(Tile::Hazard(Hazard::Saw)),
+                    '🪽' => line.push(Tile::Pickup(Ability::DoubleJump)),
+                    '🧗' => line.push(Tile::Pickup(Ability::WallJump)),
+                    '💨' => line.push(Tile::Pickup(Ability::Dash)),
+                    '👾' => line.push(Tile::Enemy(EnemyKind::Patroller)),
+                    '🐭' => line.push(Tile::Enemy(EnemyKind::Chaser)),
+                    '🐝' => line.push(Tile::Enemy(EnemyKind::Flyer)),
+                    '🔫' => line.push(Tile::Enemy(EnemyKind::Turret)),
+                    '\n' => {
+                        tiles.push(line);
+                        line = vec![];
+                    }
+                    char => Err(LevelLoaderError::UnknownTile(char))?,
+                }
+            }
+        }
+        let level = Level {
+            tiles,
+            abilities,
+            health,
+            platforms,
+        };
+        let found = level.platform_runs().len();
+        if found != level.platforms.len() {
+            Err(LevelLoaderError::PlatformCount {
+                found,
+                paths: level.platforms.len(),
+            })?;
+        }
+        Ok(level)
+    }
+}
+
+impl Level {
+    /// Number of tiles of the level, horizontally and vertically.
+    pub fn size(&self) -> UVec2 {
+        UVec2::new(
+            self.tiles.iter().map(Vec::len).max().unwrap_or(0) as u32,
+            self.tiles.len() as u32,
+        )
+    }
+
+    /// Moving platforms, as the row, first column and length of each horizontal run of platform
+    /// tiles.
+    pub fn platform_runs(&self) -> Vec<(usize, usize, usize)> {
+        let mut runs = vec![];
+        for (j, line) in self.tiles.iter().enumerate() {
+            let mut i = 0;
+            while i < line.len() {
+                let length = line[i..]
+                    .iter()
+                    .take_while(|tile| matches!(tile, Tile::Platform))
+                    .count();
+                if length > 0 {
+                    runs.push((j, i, length));
+                }
+                i += length.max(1);
+            }
+        }
+        runs
+    }
 }
 
 #[derive(Debug)]
@@ -24,17 +158,142 @@ pub enum Tile {
     Ground,
     Spawn,
     Flag,
+    Checkpoint,
+    Pickup(Ability),
+    Item(Item),
+    Key(KeyColor),
+    /// Solid until opened with a key of the same colour.
+    Door(KeyColor),
+    /// Can be jumped through from below and dropped through by pressing down.
+    OneWay,
+    /// Part of a moving platform, following one of the level platform paths.
+    Platform,
+    Enemy(EnemyKind),
+    Hazard(Hazard),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Hazard {
+    /// Takes a hit point.
+    Spikes,
+    /// Kills whatever the player health.
+    Lava,
+    /// Spinning blade, takes a hit point.
+    Saw,
+}
+
+/// Back and forth movement of a platform, as `horizontal 3 1.5` for a platform moving right by up
+/// to 3 tiles at 1.5 tiles per second.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PlatformPath {
+    pub axis: PlatformAxis,
+    /// Distance from the starting position, in tiles.
+    pub range: f32,
+    /// In tiles per second.
+    pub speed: f32,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PlatformAxis {
+    /// Moves right from its starting position.
+    Horizontal,
+    /// Moves up from its starting position.
+    Vertical,
+}
+
+impl FromStr for PlatformPath {
+    type Err = LevelLoaderError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || LevelLoaderError::InvalidPlatform(s.trim().to_string());
+        let mut parts = s.split_whitespace();
+        let axis = match parts.next() {
+            Some("horizontal") => PlatformAxis::Horizontal,
+            Some("vertical") => PlatformAxis::Vertical,
+            _ => Err(invalid())?,
+        };
+        let mut number = || {
+            parts
+                .next()
+                .and_then(|part| part.parse::<f32>().ok())
+                .filter(|number| *number > 0.0)
+                .ok_or_else(invalid)
+        };
+        let range = number()?;
+        let speed = number()?;
+        if parts.next().is_some() {
+            Err(invalid())?;
+        }
+        Ok(PlatformPath { axis, range, speed })
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KeyColor {
+    Yellow,
+    Orange,
+    Blue,
+}
+
+/// Collectibles adding to the score.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Item {
+    Coin,
+    Gem,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EnemyKind {
+    /// Walks back and forth on its platform.
+    Patroller,
+    /// Runs toward the player when close enough.
+    Chaser,
+    /// Flies around its starting point.
+    Flyer,
+    /// Shoots at the player.
+    Turret,
+}
+
+/// Optional player movement abilities.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Ability {
+    DoubleJump,
+    WallJump,
+    Dash,
+}
+
+impl FromStr for Ability {
+    type Err = LevelLoaderError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "double_jump" => Ok(Ability::DoubleJump),
+            "wall_jump" => Ok(Ability::WallJump),
+            "dash" => Ok(Ability::Dash),
+            s => Err(LevelLoaderError::UnknownAbility(s.to_string())),
+        }
+    }
 }
 
 #[derive(Default)]
 struct LevelLoader;
 
 #[derive(Debug, Error)]
-enum LevelLoaderError {
+pub enum LevelLoaderError {
     #[error("Could not load asset: {0}")]
     Io(#[from] std::io::Error),
     #[error("Unknown tile: {0}")]
     UnknownTile(char),
+    #[error("Unknown level metadata: {0}")]
+    UnknownMetadata(String),
+    #[error("Unknown ability: {0}")]
+    UnknownAbility(String),
+    #[error("Invalid player health: {0}")]
+    InvalidHealth(String),
+    #[error("Invalid platform path: {0}")]
+    InvalidPlatform(String),
+    #[error("Level has {found} moving platforms but {paths} platform paths")]
+    PlatformCount { found: usize, paths: usize },
 }
 
 impl AssetLoader for LevelLoader {
@@ -50,25 +309,61 @@ impl AssetLoader for LevelLoader {
         let mut buf = String::new();
         reader.read_to_string(&mut buf).await?;
 
-        let mut tiles = vec![];
-        let mut line = vec![];
-        for char in buf.chars() {
-            match char {
-                '⬜' => line.push(Tile::Empty),
-                '🟩' => line.push(Tile::Ground),
-                '🙂' => line.push(Tile::Spawn),
-                '🏁' => line.push(Tile::Flag),
-                '\n' => {
-                    tiles.push(line);
-                    line = vec![];
-                }
-                char => Err(LevelLoaderError::UnknownTile(char))?,
+        buf.parse()
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["bw"]
+    }
+}
+
+#[derive(Default)]
+struct CampaignLoader;
+
+#[derive(Debug, Error)]
+enum CampaignLoaderError {
+    #[error("Could not load asset: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("Missing level name on line {0}")]
+    MissingName(usize),
+    #[error("Campaign has no levels")]
+    Empty,
+}
+
+impl AssetLoader for CampaignLoader {
+    type Asset = Campaign;
+    type Settings = ();
+    type Error = CampaignLoaderError;
+    async fn load(
+        &self,
+        reader: &mut dyn Reader,
+        _settings: &(),
+        load_context: &mut LoadContext<'_>,
+    ) -> Result<Self::Asset, Self::Error> {
+        let mut buf = String::new();
+        reader.read_to_string(&mut buf).await?;
+
+        let mut levels = vec![];
+        for (number, line) in buf.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
             }
+            let Some((path, name)) = line.split_once(char::is_whitespace) else {
+                Err(CampaignLoaderError::MissingName(number + 1))?
+            };
+            levels.push(CampaignLevel {
+                name: name.trim().to_string(),
+                level: load_context.load(path.to_string()),
+            });
         }
-        Ok(Level { tiles })
+        if levels.is_empty() {
+            Err(CampaignLoaderError::Empty)?;
+        }
+        Ok(Campaign { levels })
     }
 
     fn extensions(&self) -> &[&str] {
-        &["bw"]
+        &["campaign"]
     }
 }
@@ -0,0 +1,103 @@
+use bevy::{
+    asset::{LoadState, RecursiveDependencyLoadState},
+    prelude::*,
+};
+
+use crate::GameState;
+
+pub fn loading_plugin(app: &mut App) {
+    app.init_resource::<LoadingAssets>()
+        .add_systems(Update, track_loading.run_if(in_state(GameState::Splash)))
+        .add_systems(OnEnter(GameState::LoadingFailed), display_error);
+}
+
+/// Every asset that must be ready before leaving the splash screen.
+#[derive(Resource, Default)]
+pub struct LoadingAssets {
+    handles: Vec<UntypedHandle>,
+    loaded: usize,
+    errors: Vec<String>,
+}
+
+impl LoadingAssets {
+    /// Start tracking a handle, and return it so it can be stored as usual.
+    pub fn track<A: Asset>(&mut self, handle: Handle<A>) -> Handle<A> {
+        self.handles.push(handle.clone().untyped());
+        handle
+    }
+
+    pub fn progress(&self) -> f32 {
+        if self.handles.is_empty() {
+            1.0
+        } else {
+            self.loaded as f32 / self.handles.len() as f32
+        }
+    }
+
+    pub fn is_done(&self) -> bool {
+        self.loaded == self.handles.len()
+    }
+
+    pub fn has_failed(&self) -> bool {
+        !self.errors.is_empty()
+    }
+}
+
+fn track_loading(mut loading: ResMut<LoadingAssets>, asset_server: Res<AssetServer>) {
+    let mut loaded = 0;
+    let mut errors = vec![];
+    for handle in &loading.handles {
+        let path = handle
+            .path()
+            .map(|path| path.to_string())
+            .unwrap_or_else(|| format!("{:?}", handle.id()));
+        match (
+            asset_server.load_state(handle),
+            asset_server.recursive_dependency_load_state(handle),
+        ) {
+            (LoadState::Failed(error), _) | (_, RecursiveDependencyLoadState::Failed(error)) => {
+                errors.push(format!("{path}: {error}"));
+            }
+            (LoadState::Loaded, RecursiveDependencyLoadState::Loaded) => loaded += 1,
+            _ => {}
+        }
+    }
+    loading.loaded = loaded;
+    loading.errors = errors;
+}
+
+fn display_error(mut commands: Commands, loading: Res<LoadingAssets>) {
+    commands
+        .spawn((
+            Node {
+                width: Val::Percent(100.0),
+                height: Val::Percent(100.0),
+                align_items: AlignItems::Center,
+                justify_content: JustifyContent::Center,
+                flex_direction: FlexDirection::Column,
+                row_gap: Val::Px(20.0),
+                ..default()
+            },
+            StateScoped(GameState::LoadingFailed),
+        ))
+        .with_children(|p| {
+            p.spawn((
+                Text::new("Failed to load assets"),
+                TextFont {
+                    font_size: 60.0,
+                    ..default()
+                },
+                TextColor(Color::srgb(0.9, 0.2, 0.2)),
+            ));
+            for error in &loading.errors {
+                p.spawn((
+                    Text::new(error.clone()),
+                    TextFont {
+                        font_size: 20.0,
+                        ..default()
+                    },
+                    TextLayout::new_with_justify(JustifyText::Center),
+                ));
+            }
+        });
+}
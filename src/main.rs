@@ -1,7 +1,11 @@
 use bevy::prelude::*;
 
+mod campaign;
+mod controls;
 mod game;
+mod input;
 mod level_loader;
+mod loading;
 mod menu;
 mod splash;
 
@@ -15,23 +19,41 @@ fn main() {
             }),
             ..default()
         }))
-        .init_state::<GameState>()
-        .enable_state_scoped_entities::<GameState>()
         .add_plugins((
+            app_plugin,
             splash::splash_plugin,
+            loading::loading_plugin,
+            game::rendering_plugin,
+        ))
+        .run();
+}
+
+/// States, screens and gameplay, without the engine plugins, asset loading and rendering, so that
+/// the headless tests run the same systems as the game.
+fn app_plugin(app: &mut App) {
+    app.init_state::<GameState>()
+        .enable_state_scoped_entities::<GameState>()
+        .add_plugins((
             level_loader::level_loader_plugin,
+            input::input_plugin,
             menu::menu_plugin,
+            controls::controls_plugin,
+            campaign::campaign_plugin,
             game::game_plugin,
-        ))
-        .run();
+        ));
 }
 
 #[derive(Debug, Clone, Copy, Eq, PartialEq, Hash, States, Default)]
 enum GameState {
     #[default]
     Splash,
+    LoadingFailed,
     Menu,
+    Controls,
     Game,
+    LevelComplete,
+    CampaignComplete,
+    GameOver,
 }
 
 #[derive(Resource)]
@@ -42,9 +64,22 @@ struct GameAssets {
     ground_layout: Handle<TextureAtlasLayout>,
     items_image: Handle<Image>,
     items_layout: Handle<TextureAtlasLayout>,
+    enemy_image: Handle<Image>,
+    enemy_layout: Handle<TextureAtlasLayout>,
+    tiles_image: Handle<Image>,
+    tiles_layout: Handle<TextureAtlasLayout>,
+    hud_image: Handle<Image>,
+    hud_layout: Handle<TextureAtlasLayout>,
 }
 
 #[derive(Resource)]
 struct AudioAssets {
     jump: Handle<AudioSource>,
+    double_jump: Handle<AudioSource>,
+    lost: Handle<AudioSource>,
+    checkpoint: Handle<AudioSource>,
+    pickup: Handle<AudioSource>,
+    spikes: Handle<AudioSource>,
+    lava: Handle<AudioSource>,
+    saw: Handle<AudioSource>,
 }
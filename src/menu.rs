@@ -1,13 +1,83 @@
-use bevy::{color::palettes::tailwind, prelude::*};
+use bevy::{color::palettes::tailwind, prelude::*, ui::UiSystem};
 
-use crate::GameState;
+use crate::{
+    campaign::{CampaignProgress, CurrentLevel},
+    controls::waiting_for_key,
+    input::{ActionSystem, MenuAction},
+    level_loader::{Campaign, LoadedCampaign},
+    GameState,
+};
 
 pub fn menu_plugin(app: &mut App) {
-    app.add_systems(OnEnter(GameState::Menu), display_menu)
-        .add_systems(Update, button_system.run_if(in_state(GameState::Menu)));
+    app.init_resource::<FocusedButton>()
+        .add_event::<ButtonActivated>()
+        .add_systems(OnEnter(GameState::Menu), display_menu)
+        .add_systems(
+            PreUpdate,
+            (navigate_buttons.run_if(not(waiting_for_key)), click_buttons)
+                .after(ActionSystem)
+                .after(UiSystem::Focus),
+        )
+        .add_systems(Update, button_system)
+        .add_systems(Update, menu_action.run_if(in_state(GameState::Menu)));
 }
 
-fn display_menu(mut commands: Commands) {
+/// Button selected with the keyboard, the d-pad or by hovering it, styled as hovered.
+#[derive(Resource, Default)]
+pub struct FocusedButton(pub Option<Entity>);
+
+/// A button was clicked, or confirmed while focused. Sent in `PreUpdate` so screens can react to
+/// it in `Update` of the same frame.
+#[derive(Event)]
+pub struct ButtonActivated(pub Entity);
+
+#[derive(Component)]
+enum MenuButton {
+    Play,
+    Controls,
+    Level(usize),
+}
+
+#[derive(Component)]
+struct Locked;
+
+/// Spawn a menu button, styled by [`button_system`].
+pub fn spawn_button(parent: &mut ChildBuilder, label: &str, bundle: impl Bundle) {
+    parent
+        .spawn((
+            Button,
+            Node {
+                width: Val::Px(200.0),
+                height: Val::Px(65.0),
+                border: UiRect::all(Val::Px(5.0)),
+                justify_content: JustifyContent::Center,
+                align_items: AlignItems::Center,
+                margin: UiRect::all(Val::Px(10.0)),
+                ..default()
+            },
+            BorderColor(Color::BLACK),
+            BorderRadius::MAX,
+            BackgroundColor(tailwind::BLUE_700.into()),
+            bundle,
+        ))
+        .with_child((
+            Text::new(label),
+            TextFont {
+                font_size: 33.0,
+                ..default()
+            },
+            TextColor(Color::srgb(0.9, 0.9, 0.9)),
+        ));
+}
+
+fn display_menu(
+    mut commands: Commands,
+    campaign: Res<LoadedCampaign>,
+    campaigns: Res<Assets<Campaign>>,
+    progress: Res<CampaignProgress>,
+) {
+    let campaign = campaigns.get(&campaign.campaign).unwrap();
+
     commands
         .spawn((
             Node {
@@ -29,55 +99,289 @@ fn display_menu(mut commands: Commands) {
                 },
                 TextLayout::new_with_justify(JustifyText::Center),
             ));
+            spawn_button(p, "Play", MenuButton::Play);
+            spawn_button(p, "Controls", MenuButton::Controls);
             p.spawn((
-                Button,
-                Node {
-                    width: Val::Px(150.0),
-                    height: Val::Px(65.0),
-                    border: UiRect::all(Val::Px(5.0)),
-                    justify_content: JustifyContent::Center,
-                    align_items: AlignItems::Center,
-                    margin: UiRect::all(Val::Px(50.0)),
-                    ..default()
-                },
-                BorderColor(Color::BLACK),
-                BorderRadius::MAX,
-                BackgroundColor(tailwind::BLUE_700.into()),
-            ))
-            .with_child((
-                Text::new("Play"),
+                Text::new("Levels"),
                 TextFont {
-                    font_size: 33.0,
+                    font_size: 40.0,
                     ..default()
                 },
-                TextColor(Color::srgb(0.9, 0.9, 0.9)),
             ));
+            p.spawn(Node {
+                width: Val::Px(600.0),
+                flex_wrap: FlexWrap::Wrap,
+                justify_content: JustifyContent::Center,
+                ..default()
+            })
+            .with_children(|p| {
+                for (index, level) in campaign.levels.iter().enumerate() {
+                    let unlocked = progress.is_unlocked(index);
+                    let status = if !unlocked {
+                        "Locked"
+                    } else if progress.is_completed(index) {
+                        "Done"
+                    } else {
+                        ""
+                    };
+                    let mut button = p.spawn((
+                        Button,
+                        Node {
+                            width: Val::Px(170.0),
+                            height: Val::Px(90.0),
+                            border: UiRect::all(Val::Px(5.0)),
+                            justify_content: JustifyContent::Center,
+                            align_items: AlignItems::Center,
+                            flex_direction: FlexDirection::Column,
+                            margin: UiRect::all(Val::Px(10.0)),
+                            ..default()
+                        },
+                        BorderColor(Color::BLACK),
+                        BorderRadius::all(Val::Px(10.0)),
+                        MenuButton::Level(index),
+                    ));
+                    if unlocked {
+                        button.insert(BackgroundColor(tailwind::BLUE_700.into()));
+                    } else {
+                        button.insert((BackgroundColor(tailwind::GRAY_600.into()), Locked));
+                    }
+                    button.with_children(|p| {
+                        p.spawn((
+                            Text::new(format!("{}. {}", index + 1, level.name)),
+                            TextFont {
+                                font_size: 20.0,
+                                ..default()
+                            },
+                            TextLayout::new_with_justify(JustifyText::Center),
+                            TextColor(Color::srgb(0.9, 0.9, 0.9)),
+                        ));
+                        p.spawn((
+                            Text::new(status),
+                            TextFont {
+                                font_size: 16.0,
+                                ..default()
+                            },
+                            TextColor(Color::srgb(0.7, 0.7, 0.7)),
+                        ));
+                    });
+                }
+            });
         });
 }
 
 #[allow(clippy::type_complexity)]
 fn button_system(
     mut interaction_query: Query<
-        (&Interaction, &mut BackgroundColor, &mut BorderColor),
-        (Changed<Interaction>, With<Button>),
+        (
+            Entity,
+            Ref<Interaction>,
+            &mut BackgroundColor,
+            &mut BorderColor,
+        ),
+        (With<Button>, Without<Locked>),
     >,
-    mut next: ResMut<NextState<GameState>>,
+    focused: Res<FocusedButton>,
 ) {
-    for (interaction, mut color, mut border_color) in &mut interaction_query {
-        match *interaction {
-            Interaction::Pressed => {
+    for (entity, interaction, mut color, mut border_color) in &mut interaction_query {
+        if !interaction.is_changed() && !focused.is_changed() {
+            continue;
+        }
+        match (*interaction, focused.0 == Some(entity)) {
+            (Interaction::Pressed, _) => {
                 *color = tailwind::VIOLET_500.into();
                 border_color.0 = tailwind::RED_600.into();
-                next.set(GameState::Game);
             }
-            Interaction::Hovered => {
+            (Interaction::Hovered, _) | (Interaction::None, true) => {
                 *color = tailwind::BLUE_500.into();
                 border_color.0 = tailwind::BLUE_700.into();
             }
-            Interaction::None => {
+            (Interaction::None, false) => {
                 *color = tailwind::BLUE_700.into();
                 border_color.0 = tailwind::BLUE_900.into();
             }
         }
     }
 }
+
+#[allow(clippy::type_complexity)]
+fn click_buttons(
+    interaction_query: Query<
+        (Entity, &Interaction),
+        (Changed<Interaction>, With<Button>, Without<Locked>),
+    >,
+    mut focused: ResMut<FocusedButton>,
+    mut activated: EventWriter<ButtonActivated>,
+) {
+    for (entity, interaction) in &interaction_query {
+        match interaction {
+            Interaction::Pressed => {
+                activated.send(ButtonActivated(entity));
+            }
+            Interaction::Hovered => focused.0 = Some(entity),
+            Interaction::None => {}
+        }
+    }
+}
+
+#[allow(clippy::type_complexity)]
+fn navigate_buttons(
+    actions: Res<ButtonInput<MenuAction>>,
+    buttons: Query<(Entity, &GlobalTransform), (With<Button>, Without<Locked>)>,
+    mut focused: ResMut<FocusedButton>,
+    mut activated: EventWriter<ButtonActivated>,
+) {
+    let current = focused
+        .0
+        .and_then(|entity| buttons.get(entity).ok())
+        .map(|(entity, transform)| (entity, transform.translation().xy()));
+    let Some((current, position)) = current else {
+        // Nothing focused on this screen yet, start from the first button
+        if actions.get_just_pressed().next().is_some() {
+            focused.0 = buttons
+                .iter()
+                .min_by(|(_, a), (_, b)| {
+                    let (a, b) = (a.translation(), b.translation());
+                    a.y.total_cmp(&b.y).then(a.x.total_cmp(&b.x))
+                })
+                .map(|(entity, _)| entity);
+        }
+        return;
+    };
+
+    if actions.just_pressed(MenuAction::Confirm) {
+        activated.send(ButtonActivated(current));
+        return;
+    }
+    // UI coordinates grow downwards
+    let direction = if actions.just_pressed(MenuAction::Up) {
+        Vec2::NEG_Y
+    } else if actions.just_pressed(MenuAction::Down) {
+        Vec2::Y
+    } else if actions.just_pressed(MenuAction::Left) {
+        Vec2::NEG_X
+    } else if actions.just_pressed(MenuAction::Right) {
+        Vec2::X
+    } else {
+        return;
+    };
+    let candidates = buttons
+        .iter()
+        .filter(|(entity, _)| *entity != current)
+        .map(|(entity, transform)| (entity, transform.translation().xy()));
+    if let Some(next) = closest_in_direction(position, direction, candidates) {
+        focused.0 = Some(next);
+    }
+}
+
+/// Closest button in `direction` from `from`, favouring buttons aligned with it.
+fn closest_in_direction(
+    from: Vec2,
+    direction: Vec2,
+    candidates: impl Iterator<Item = (Entity, Vec2)>,
+) -> Option<Entity> {
+    candidates
+        .filter_map(|(entity, position)| {
+            let offset = position - from;
+            let along = offset.dot(direction);
+            let across = offset.perp_dot(direction).abs();
+            (along > 0.0).then_some((entity, along + across * 2.0))
+        })
+        .min_by(|(_, a), (_, b)| a.total_cmp(b))
+        .map(|(entity, _)| entity)
+}
+
+fn menu_action(
+    mut activated: EventReader<ButtonActivated>,
+    buttons: Query<&MenuButton>,
+    mut next: ResMut<NextState<GameState>>,
+    mut current_level: ResMut<CurrentLevel>,
+) {
+    for ButtonActivated(entity) in activated.read() {
+        let Ok(button) = buttons.get(*entity) else {
+            continue;
+        };
+        match button {
+            MenuButton::Play => {
+                current_level.0 = 0;
+                next.set(GameState::Game);
+            }
+            MenuButton::Controls => next.set(GameState::Controls),
+            MenuButton::Level(index) => {
+                current_level.0 = *index;
+                next.set(GameState::Game);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::input::{
+        keyboard::{Key, KeyboardInput, NativeKey},
+        ButtonState, InputPlugin,
+    };
+
+    use super::*;
+    use crate::input::input_plugin;
+
+    #[test]
+    fn focus_moves_to_closest_aligned_button() {
+        let play = Entity::from_raw(0);
+        let controls = Entity::from_raw(1);
+        let level = Entity::from_raw(2);
+        let buttons = [
+            (play, Vec2::new(400.0, 100.0)),
+            (controls, Vec2::new(400.0, 200.0)),
+            (level, Vec2::new(250.0, 320.0)),
+        ];
+
+        let down = closest_in_direction(Vec2::new(400.0, 100.0), Vec2::Y, buttons.into_iter());
+        assert_eq!(down, Some(controls));
+        let down = closest_in_direction(Vec2::new(400.0, 200.0), Vec2::Y, buttons.into_iter());
+        assert_eq!(down, Some(level));
+        let up = closest_in_direction(Vec2::new(400.0, 100.0), Vec2::NEG_Y, buttons.into_iter());
+        assert_eq!(up, None);
+    }
+
+    fn press(app: &mut App, key_code: KeyCode) {
+        for state in [ButtonState::Pressed, ButtonState::Released] {
+            app.world_mut().send_event(KeyboardInput {
+                key_code,
+                logical_key: Key::Unidentified(NativeKey::Unidentified),
+                state,
+                repeat: false,
+                window: Entity::PLACEHOLDER,
+            });
+            app.update();
+        }
+    }
+
+    #[test]
+    fn keyboard_moves_focus_and_activates() {
+        let mut app = App::new();
+        app.add_plugins((MinimalPlugins, InputPlugin, input_plugin))
+            .init_resource::<FocusedButton>()
+            .add_event::<ButtonActivated>()
+            .add_systems(PreUpdate, navigate_buttons.after(ActionSystem));
+        let [play, controls] = [100.0, 200.0].map(|y| {
+            app.world_mut()
+                .spawn((Button, GlobalTransform::from_xyz(400.0, y, 0.0)))
+                .id()
+        });
+        app.update();
+
+        press(&mut app, KeyCode::ArrowDown);
+        assert_eq!(app.world().resource::<FocusedButton>().0, Some(play));
+        press(&mut app, KeyCode::ArrowDown);
+        assert_eq!(app.world().resource::<FocusedButton>().0, Some(controls));
+
+        press(&mut app, KeyCode::Enter);
+        let activated = app.world().resource::<Events<ButtonActivated>>();
+        let activated = activated
+            .get_cursor()
+            .read(activated)
+            .map(|ButtonActivated(entity)| *entity)
+            .collect::<Vec<_>>();
+        assert_eq!(activated, [controls]);
+    }
+}
@@ -1,10 +1,15 @@
 use bevy::prelude::*;
 
-use crate::{level_loader::LoadedLevel, AudioAssets, GameAssets, GameState};
+use crate::{
+    level_loader::LoadedCampaign, loading::LoadingAssets, AudioAssets, GameAssets, GameState,
+};
 
 pub fn splash_plugin(app: &mut App) {
     app.add_systems(OnEnter(GameState::Splash), (display_title, load_assets))
-        .add_systems(Update, switch_to_menu.run_if(in_state(GameState::Splash)));
+        .add_systems(
+            Update,
+            (update_progress_bar, switch_to_menu).run_if(in_state(GameState::Splash)),
+        );
 }
 
 fn display_title(mut commands: Commands) {
@@ -17,6 +22,7 @@ fn display_title(mut commands: Commands) {
                 height: Val::Percent(100.0),
                 align_items: AlignItems::Center,
                 justify_content: JustifyContent::Center,
+                flex_direction: FlexDirection::Column,
                 ..default()
             },
             StateScoped(GameState::Splash),
@@ -30,6 +36,25 @@ fn display_title(mut commands: Commands) {
                 },
                 TextLayout::new_with_justify(JustifyText::Center),
             ));
+            p.spawn((
+                Node {
+                    width: Val::Px(400.0),
+                    height: Val::Px(20.0),
+                    margin: UiRect::top(Val::Px(40.0)),
+                    border: UiRect::all(Val::Px(2.0)),
+                    ..default()
+                },
+                BorderColor(Color::srgb(0.9, 0.9, 0.9)),
+            ))
+            .with_child((
+                Node {
+                    width: Val::Percent(0.0),
+                    height: Val::Percent(100.0),
+                    ..default()
+                },
+                BackgroundColor(Color::srgb(0.9, 0.9, 0.9)),
+                ProgressBar,
+            ));
         });
 
     commands.insert_resource(SplashScreenTimer(Timer::from_seconds(2.0, TimerMode::Once)));
@@ -38,12 +63,27 @@ fn display_title(mut commands: Commands) {
 #[derive(Resource)]
 struct SplashScreenTimer(Timer);
 
+#[derive(Component)]
+struct ProgressBar;
+
+fn update_progress_bar(
+    loading: Res<LoadingAssets>,
+    mut progress_bar: Query<&mut Node, With<ProgressBar>>,
+) {
+    for mut node in &mut progress_bar {
+        node.width = Val::Percent(loading.progress() * 100.0);
+    }
+}
+
 fn switch_to_menu(
     mut next: ResMut<NextState<GameState>>,
     mut timer: ResMut<SplashScreenTimer>,
     time: Res<Time>,
+    loading: Res<LoadingAssets>,
 ) {
-    if timer.0.tick(time.delta()).just_finished() {
+    if loading.has_failed() {
+        next.set(GameState::LoadingFailed);
+    } else if timer.0.tick(time.delta()).finished() && loading.is_done() {
         next.set(GameState::Menu);
     }
 }
@@ -52,12 +92,13 @@ fn load_assets(
     mut commands: Commands,
     asset_server: Res<AssetServer>,
     mut texture_atlas_layouts: ResMut<Assets<TextureAtlasLayout>>,
+    mut loading: ResMut<LoadingAssets>,
 ) {
-    commands.insert_resource(LoadedLevel {
-        level: asset_server.load("level.bw"),
+    commands.insert_resource(LoadedCampaign {
+        campaign: loading.track(asset_server.load("main.campaign")),
     });
     commands.insert_resource(GameAssets {
-        player_image: asset_server.load("spritesheet_players.png"),
+        player_image: loading.track(asset_server.load("spritesheet_players.png")),
         player_layout: texture_atlas_layouts.add(TextureAtlasLayout::from_grid(
             UVec2::new(128, 256),
             7,
@@ -65,7 +106,7 @@ fn load_assets(
             None,
             None,
         )),
-        ground_image: asset_server.load("spritesheet_ground.png"),
+        ground_image: loading.track(asset_server.load("spritesheet_ground.png")),
         ground_layout: texture_atlas_layouts.add(TextureAtlasLayout::from_grid(
             UVec2::new(128, 128),
             7,
@@ -73,7 +114,7 @@ fn load_assets(
             None,
             None,
         )),
-        items_image: asset_server.load("spritesheet_items.png"),
+        items_image: loading.track(asset_server.load("spritesheet_items.png")),
         items_layout: texture_atlas_layouts.add(TextureAtlasLayout::from_grid(
             UVec2::new(128, 128),
             6,
@@ -81,8 +122,39 @@ fn load_assets(
             None,
             None,
         )),
+        enemy_image: loading.track(asset_server.load("spritesheet_enemies.png")),
+        enemy_layout: texture_atlas_layouts.add(TextureAtlasLayout::from_grid(
+            UVec2::new(128, 128),
+            8,
+            16,
+            None,
+            None,
+        )),
+        tiles_image: loading.track(asset_server.load("spritesheet_tiles.png")),
+        tiles_layout: texture_atlas_layouts.add(TextureAtlasLayout::from_grid(
+            UVec2::new(128, 128),
+            8,
+            16,
+            None,
+            None,
+        )),
+        hud_image: loading.track(asset_server.load("spritesheet_hud.png")),
+        hud_layout: texture_atlas_layouts.add(TextureAtlasLayout::from_grid(
+            UVec2::new(128, 128),
+            8,
+            8,
+            None,
+            None,
+        )),
     });
     commands.insert_resource(AudioAssets {
-        jump: asset_server.load("jump.wav"),
+        jump: loading.track(asset_server.load("jump.wav")),
+        double_jump: loading.track(asset_server.load("double_jump.wav")),
+        lost: loading.track(asset_server.load("lost.wav")),
+        checkpoint: loading.track(asset_server.load("start.wav")),
+        pickup: loading.track(asset_server.load("win.wav")),
+        spikes: loading.track(asset_server.load("spikes.wav")),
+        lava: loading.track(asset_server.load("lava.wav")),
+        saw: loading.track(asset_server.load("saw.wav")),
     });
 }